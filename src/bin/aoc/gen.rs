@@ -0,0 +1,489 @@
+use std::{collections::HashSet, fmt::Write};
+use itertools::Itertools;
+use miette::{miette, Result};
+use super::rng::Rng;
+
+/// What `--size` means for a day, and its size when none is given
+pub fn size_of(day: u32) -> Option<(&'static str, usize)> {
+    Some(match day {
+        1 => ("lines", 1000),
+        2 => ("reports", 1000),
+        3 => ("characters", 20_000),
+        4 => ("grid side", 140),
+        5 => ("updates", 200),
+        6 => ("grid side", 130),
+        7 => ("equations", 850),
+        8 => ("grid side", 50),
+        9 => ("digits", 19_999),
+        10 => ("grid side", 50),
+        11 => ("stones", 8),
+        12 => ("grid side", 140),
+        13 => ("claw machines", 320),
+        14 => ("robots", 500),
+        15 => ("grid side", 50),
+        16 => ("maze side", 141),
+        17 => ("unused, programs are always 16 values", 16),
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub size: usize,
+    /// Width and height of the day 14 arena
+    pub arena: (i64, i64),
+}
+
+/// A puzzle input, with an answer when the generator knows it by construction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generated {
+    pub input: String,
+    pub answer: Option<String>,
+}
+
+impl From<String> for Generated {
+    fn from(input: String) -> Self {
+        Self { input, answer: None }
+    }
+}
+
+pub fn generate(day: u32, seed: u64, options: &Options) -> Result<Generated> {
+    let rng = &mut Rng::new(seed);
+    let size = options.size;
+    let mut generated = match day {
+        1 => day1(rng, size).into(),
+        2 => day2(rng, size).into(),
+        3 => day3(rng, size).into(),
+        4 => day4(rng, size).into(),
+        5 => day5(rng, size).into(),
+        6 => day6(rng, size.max(2)).into(),
+        7 => day7(rng, size).into(),
+        8 => day8(rng, size).into(),
+        9 => day9(rng, size).into(),
+        10 => day10(rng, size).into(),
+        11 => day11(rng, size).into(),
+        12 => day12(rng, size).into(),
+        13 => day13(rng, size).into(),
+        14 => day14(rng, size, options.arena)?,
+        15 => day15(rng, size.max(5)).into(),
+        16 => day16(rng, size.max(5)).into(),
+        17 => day17(rng),
+        _ => return Err(miette!("There is no generator for day {day}")),
+    };
+    generated.input.push('\n');
+
+    Ok(generated)
+}
+
+fn grid(rows: &[Vec<char>]) -> String {
+    rows.iter().map(|row| row.iter().collect::<String>()).join("\n")
+}
+
+fn day1(rng: &mut Rng, size: usize) -> String {
+    let left = (0..size).map(|_| rng.range(10_000..=99_999)).collect_vec();
+    left.iter().map(|l| {
+        // Repeat some ids so the similarity score isn't zero
+        let right = if rng.chance(0.3) { *rng.pick(&left) } else { rng.range(10_000..=99_999) };
+        format!("{l}   {right}")
+    }).join("\n")
+}
+
+fn day2(rng: &mut Rng, size: usize) -> String {
+    (0..size).map(|_| {
+        let direction = if rng.chance(0.5) { 1 } else { -1 };
+        let mut level = rng.range(30..=70);
+        let mut levels = (0..rng.range(5..=8)).map(|_| {
+            level += direction * rng.range(1..=3);
+            level
+        }).collect_vec();
+        if rng.chance(0.5) {
+            let index = rng.below(levels.len());
+            levels[index] += rng.range(-4..=4);
+        }
+        levels.iter().join(" ")
+    }).join("\n")
+}
+
+fn day3(rng: &mut Rng, size: usize) -> String {
+    const NOISE: [&str; 20] = ["mul", "mul(", "(", ")", ",", "do", "don't", "[", "]", "!", "@", "#", "%", "^", "&", "*", "'", " ", "what()", "from(1,2)"];
+    let mut memory = String::with_capacity(size + 16);
+    while memory.len() < size {
+        match rng.below(12) {
+            0..=2 => write!(memory, "mul({},{})", rng.range(1..=999), rng.range(1..=999)).unwrap(),
+            3 => memory.push_str("do()"),
+            4 => memory.push_str("don't()"),
+            _ => {
+                let noise = rng.pick(&NOISE);
+                memory.push_str(noise);
+            },
+        }
+    }
+
+    memory
+}
+
+fn day4(rng: &mut Rng, size: usize) -> String {
+    grid(&(0..size).map(|_| (0..size).map(|_| *rng.pick(&['X', 'M', 'A', 'S'])).collect()).collect_vec())
+}
+
+fn day5(rng: &mut Rng, size: usize) -> String {
+    // Every pair of pages gets a rule, so each update has exactly one correct order
+    let mut pages = (10..=99).collect_vec();
+    rng.shuffle(&mut pages);
+    pages.truncate(49);
+    let mut rules = pages.iter().tuple_combinations().map(|(before, after)| format!("{before}|{after}")).collect_vec();
+    rng.shuffle(&mut rules);
+
+    let updates = (0..size).map(|_| {
+        let mut update = pages.clone();
+        rng.shuffle(&mut update);
+        update.truncate(rng.range(2..=11) as usize * 2 + 1);
+        if rng.chance(0.5) {
+            update.sort_by_key(|page| pages.iter().position(|p| p == page));
+        }
+        update.iter().join(",")
+    }).join("\n");
+
+    format!("{}\n\n{updates}", rules.join("\n"))
+}
+
+/// Whether the guard walks off the map instead of going in circles
+fn guard_leaves(map: &[Vec<char>], mut position: (i64, i64)) -> bool {
+    let (width, height) = (map[0].len() as i64, map.len() as i64);
+    let mut direction = (0, -1);
+    let mut seen = HashSet::new();
+    while seen.insert((position, direction)) {
+        let next = (position.0 + direction.0, position.1 + direction.1);
+        if next.0 < 0 || next.1 < 0 || next.0 >= width || next.1 >= height {
+            return true;
+        }
+        if map[next.1 as usize][next.0 as usize] == '#' {
+            direction = (-direction.1, direction.0);
+        } else {
+            position = next;
+        }
+    }
+
+    false
+}
+
+fn day6(rng: &mut Rng, size: usize) -> String {
+    loop {
+        let mut map = (0..size).map(|_| (0..size).map(|_| if rng.chance(0.1) { '#' } else { '.' }).collect_vec()).collect_vec();
+        let guard = (rng.below(size), rng.below(size));
+        map[guard.1][guard.0] = '^';
+        if guard_leaves(&map, (guard.0 as i64, guard.1 as i64)) {
+            return grid(&map);
+        }
+    }
+}
+
+fn day7(rng: &mut Rng, size: usize) -> String {
+    // At most seven numbers below 100 keep every intermediate result well inside a u64
+    (0..size).map(|_| {
+        let numbers = (0..rng.range(3..=7)).map(|_| rng.range(1..=99) as u64).collect_vec();
+        let result = if rng.chance(0.6) {
+            numbers[1..].iter().fold(numbers[0], |acc, n| match rng.below(3) {
+                0 => acc + n,
+                1 => acc * n,
+                _ => format!("{acc}{n}").parse().unwrap(),
+            })
+        } else {
+            rng.range(1..=99_999_999) as u64
+        };
+        format!("{result}: {}", numbers.iter().join(" "))
+    }).join("\n")
+}
+
+fn day8(rng: &mut Rng, size: usize) -> String {
+    let frequencies = ('a'..='z').chain('A'..='Z').chain('0'..='9').collect_vec();
+    let frequencies = &frequencies[..rng.range(1..=frequencies.len() as i64) as usize];
+    let mut map = vec![vec!['.'; size]; size];
+    (0..size * size / 25).for_each(|_| map[rng.below(size)][rng.below(size)] = *rng.pick(frequencies));
+
+    grid(&map)
+}
+
+fn day9(rng: &mut Rng, size: usize) -> String {
+    (0..size).map(|i| if i % 2 == 0 { rng.range(1..=9) } else { rng.range(0..=9) }).join("")
+}
+
+const NEIGHBOURS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+fn neighbours(size: usize, (x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    NEIGHBOURS.iter()
+        .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+        .filter(move |(x, y)| (0..size as i64).contains(x) && (0..size as i64).contains(y))
+        .map(|(x, y)| (x as usize, y as usize))
+}
+
+fn day10(rng: &mut Rng, size: usize) -> String {
+    let mut map = (0..size).map(|_| (0..size).map(|_| rng.range(0..=9) as u8).collect_vec()).collect_vec();
+    // Random heights rarely form trails, so walk some in
+    (0..size / 2).for_each(|_| {
+        let mut trail = vec![(rng.below(size), rng.below(size))];
+        while trail.len() < 10 {
+            let options = neighbours(size, *trail.last().unwrap()).filter(|cell| !trail.contains(cell)).collect_vec();
+            if options.is_empty() {
+                return;
+            }
+            trail.push(*rng.pick(&options));
+        }
+        trail.iter().enumerate().for_each(|(height, (x, y))| map[*y][*x] = height as u8);
+    });
+
+    map.iter().map(|row| row.iter().join("")).join("\n")
+}
+
+fn day11(rng: &mut Rng, size: usize) -> String {
+    (0..size).map(|_| if rng.chance(0.5) { rng.range(0..=9_999) } else { rng.range(10_000..=9_999_999) }).join(" ")
+}
+
+fn day12(rng: &mut Rng, size: usize) -> String {
+    let mut map = vec![vec!['A'; size]; size];
+    (0..size).cartesian_product(0..size).for_each(|(y, x)| {
+        // Copying a neighbour grows regions instead of single plots
+        map[y][x] = match (x, y, rng.below(8)) {
+            (x, _, 0..=2) if x > 0 => map[y][x - 1],
+            (_, y, 3..=5) if y > 0 => map[y - 1][x],
+            _ => (b'A' + rng.below(26) as u8) as char,
+        };
+    });
+
+    grid(&map)
+}
+
+fn day13(rng: &mut Rng, size: usize) -> String {
+    (0..size).map(|_| {
+        let a = (rng.range(10..=99), rng.range(10..=99));
+        let b = (rng.range(10..=99), rng.range(10..=99));
+        let prize = if rng.chance(0.5) {
+            let (presses_a, presses_b) = (rng.range(1..=100), rng.range(1..=100));
+            (a.0 * presses_a + b.0 * presses_b, a.1 * presses_a + b.1 * presses_b)
+        } else {
+            (rng.range(1_000..=20_000), rng.range(1_000..=20_000))
+        };
+        format!("Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}", a.0, a.1, b.0, b.1, prize.0, prize.1)
+    }).join("\n\n")
+}
+
+fn day14(rng: &mut Rng, size: usize, (width, height): (i64, i64)) -> Result<Generated> {
+    if width < 1 || height < 1 || size as i64 > width * height {
+        return Err(miette!("{size} robots don't fit on a {width}x{height} arena"));
+    }
+
+    // Half the robots draw a tree at some second, where no two robots overlap
+    let second = rng.range(1..=width * height - 1);
+    let mut cells = vec![];
+    let mut taken = HashSet::new();
+    let (mut row, top) = (0, rng.range(0..=height / 2));
+    while top + row < height && 2 * row < width && cells.len() + (2 * row as usize) < size / 2 {
+        (width / 2 - row..=width / 2 + row).for_each(|x| cells.push((x, top + row)));
+        row += 1;
+    }
+    taken.extend(cells.iter().copied());
+    while cells.len() < size {
+        let cell = (rng.range(0..=width - 1), rng.range(0..=height - 1));
+        if taken.insert(cell) {
+            cells.push(cell);
+        }
+    }
+    rng.shuffle(&mut cells);
+
+    let input = cells.iter().map(|(x, y)| {
+        let velocity = (rng.range(-99..=99), rng.range(-99..=99));
+        let start = ((x - velocity.0 * second).rem_euclid(width), (y - velocity.1 * second).rem_euclid(height));
+        format!("p={},{} v={},{}", start.0, start.1, velocity.0, velocity.1)
+    }).join("\n");
+
+    Ok(Generated { input, answer: None })
+}
+
+fn day15(rng: &mut Rng, size: usize) -> String {
+    let mut map = (0..size).map(|y| (0..size).map(|x| match rng.below(20) {
+        _ if x == 0 || y == 0 || x == size - 1 || y == size - 1 => '#',
+        0 => '#',
+        1..=4 => 'O',
+        _ => '.',
+    }).collect_vec()).collect_vec();
+    map[size / 2][size / 2] = '@';
+
+    let moves = (0..size * 20).map(|_| *rng.pick(&['^', '>', 'v', '<'])).collect_vec();
+    let moves = moves.chunks(70).map(|line| line.iter().collect::<String>()).join("\n");
+
+    format!("{}\n\n{moves}", grid(&map))
+}
+
+fn day16(rng: &mut Rng, size: usize) -> String {
+    // A maze carved between odd cells connects all of them, so S always reaches E
+    let size = size | 1;
+    let mut map = vec![vec!['#'; size]; size];
+    let mut stack = vec![(1, 1)];
+    map[1][1] = '.';
+    while let Some(&(x, y)) = stack.last() {
+        let options = NEIGHBOURS.iter()
+            .map(|(dx, dy)| ((x as i64 + 2 * dx) as usize, (y as i64 + 2 * dy) as usize))
+            .filter(|(nx, ny)| (1..size - 1).contains(nx) && (1..size - 1).contains(ny) && map[*ny][*nx] == '#')
+            .collect_vec();
+        if options.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, ny) = *rng.pick(&options);
+        map[(y + ny) / 2][(x + nx) / 2] = '.';
+        map[ny][nx] = '.';
+        stack.push((nx, ny));
+    }
+
+    // Knock out some walls so there is more than one way through
+    (1..size - 1).cartesian_product(1..size - 1).for_each(|(y, x)| {
+        let between = (map[y][x - 1] == '.' && map[y][x + 1] == '.') || (map[y - 1][x] == '.' && map[y + 1][x] == '.');
+        if map[y][x] == '#' && between && rng.chance(0.05) {
+            map[y][x] = '.';
+        }
+    });
+    map[size - 2][1] = 'S';
+    map[1][size - 2] = 'E';
+
+    grid(&map)
+}
+
+/// Runs a day 17 program, shifts past the register width give zero
+pub fn run_program(program: &[u8], mut a: u64) -> Vec<u8> {
+    let (mut b, mut c, mut pointer, mut output) = (0u64, 0u64, 0, vec![]);
+    while pointer + 1 < program.len() {
+        let literal = program[pointer + 1] as u64;
+        let combo = match literal {
+            4 => a,
+            5 => b,
+            6 => c,
+            _ => literal,
+        };
+        let shifted = a.checked_shr(combo as u32).unwrap_or(0);
+        match program[pointer] {
+            0 => a = shifted,
+            1 => b ^= literal,
+            2 => b = combo % 8,
+            3 if a != 0 => {
+                pointer = literal as usize;
+                continue;
+            },
+            4 => b ^= c,
+            5 => output.push((combo % 8) as u8),
+            6 => b = shifted,
+            7 => c = shifted,
+            _ => {},
+        }
+        pointer += 2;
+    }
+
+    output
+}
+
+/// Smallest register A that makes the program print itself, for programs that drop three bits of A per output
+pub fn quine(program: &[u8]) -> Option<u64> {
+    fn search(program: &[u8], index: usize, a: u64) -> Option<u64> {
+        (0..8).map(|digit| a << 3 | digit).find_map(|candidate| {
+            if run_program(program, candidate) != program[index..] {
+                return None;
+            }
+            if index == 0 { Some(candidate) } else { search(program, index - 1, candidate) }
+        })
+    }
+
+    search(program, program.len().checked_sub(1)?, 0)
+}
+
+fn day17(rng: &mut Rng) -> Generated {
+    // The shape of the real programs, operands stay below 7 as the solution reads every operand as a combo
+    loop {
+        let (x, y, z) = (rng.range(0..=6) as u8, rng.range(0..=6) as u8, rng.range(0..=6) as u8);
+        let program = [2, 4, 1, x, 7, 5, 1, y, 4, z, 0, 3, 5, 5, 3, 0];
+        if let Some(answer) = quine(&program) {
+            let input = format!("Register A: {}\nRegister B: 0\nRegister C: 0\n\nProgram: {}", rng.range(1..=1 << 40), program.iter().join(","));
+            return Generated { input, answer: Some(answer.to_string()) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+    use itertools::Itertools;
+    use super::{generate, neighbours, quine, run_program, size_of, Options};
+
+    /// Shortest number of steps from `from` to `to` through open cells
+    fn distance(map: &[Vec<char>], from: (usize, usize), to: (usize, usize)) -> Option<usize> {
+        let mut queue = VecDeque::from([(from, 0)]);
+        let mut seen = HashSet::from([from]);
+        while let Some((cell, steps)) = queue.pop_front() {
+            if cell == to {
+                return Some(steps);
+            }
+            neighbours(map.len(), cell)
+                .filter(|(x, y)| map[*y][*x] != '#' && seen.insert((*x, *y)))
+                .collect_vec()
+                .into_iter()
+                .for_each(|next| queue.push_back((next, steps + 1)));
+        }
+
+        None
+    }
+
+    fn options(size: usize) -> Options {
+        Options { size, arena: (11, 7) }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        (1..=17).for_each(|day| {
+            let size = size_of(day).unwrap().1.min(40);
+            let first = generate(day, 7, &options(size)).unwrap();
+            assert_eq!(first, generate(day, 7, &options(size)).unwrap(), "day {day}");
+            assert_ne!(first.input, generate(day, 8, &options(size)).unwrap().input, "day {day}");
+            assert!(first.input.ends_with('\n') && !first.input.ends_with("\n\n"), "day {day}");
+        });
+        assert!(generate(18, 7, &options(1)).is_err());
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(generate(1, 1, &options(25)).unwrap().input.lines().count(), 25);
+        assert_eq!(generate(9, 1, &options(99)).unwrap().input.trim().len(), 99);
+        assert!(generate(3, 1, &options(500)).unwrap().input.len() > 500);
+        let map = generate(12, 1, &options(30)).unwrap().input;
+        assert!(map.lines().all(|line| line.len() == 30) && map.lines().count() == 30);
+    }
+
+    #[test]
+    fn test_maze_has_path() {
+        (0..20).for_each(|seed| {
+            let maze = generate(16, seed, &options(21)).unwrap().input.lines().map(|line| line.chars().collect_vec()).collect_vec();
+            let find = |c| maze.iter().enumerate().find_map(|(y, row)| Some((row.iter().position(|x| *x == c)?, y))).unwrap();
+            assert!(distance(&maze, find('S'), find('E')).is_some());
+        });
+    }
+
+    #[test]
+    fn test_robots_meet_without_overlap() {
+        (0..10).for_each(|seed| {
+            let robots = generate(14, seed, &options(30)).unwrap().input.lines()
+                .map(|line| line.split(['=', ',', ' ']).filter_map(|n| n.parse::<i64>().ok()).collect_vec())
+                .collect_vec();
+            assert_eq!(robots.len(), 30);
+            assert!((1..77).any(|t| robots.iter().map(|r| ((r[0] + r[2] * t).rem_euclid(11), (r[1] + r[3] * t).rem_euclid(7))).all_unique()));
+        });
+        assert!(generate(14, 0, &options(78)).is_err());
+    }
+
+    #[test]
+    fn test_quine() {
+        let generated = generate(17, 3, &options(16)).unwrap();
+        let program = generated.input.split("Program: ").nth(1).unwrap().trim().split(',').map(|n| n.parse().unwrap()).collect_vec();
+        let answer = generated.answer.unwrap().parse().unwrap();
+        assert_eq!(run_program(&program, answer), program);
+        assert!((0..answer.min(100_000)).all(|a| run_program(&program, a) != program));
+        // The example from the puzzle
+        assert_eq!(quine(&[0, 3, 5, 4, 3, 0]), Some(117_440));
+    }
+}
@@ -0,0 +1,174 @@
+mod gen;
+mod report;
+mod run;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+mod budget;
+#[path = "../../rng.rs"]
+mod rng;
+
+use std::path::{Path, PathBuf};
+use miette::{miette, IntoDiagnostic, Result};
+use report::{bench_days, run_day, Report};
+use run::{Limits, Status};
+
+const USAGE: &str = "Usage:
+  aoc bench [--day N]... [--json PATH] [--baseline PATH] [--threshold PERCENT]
+  aoc gen --day N [--size N] [--seed N] [--arena WIDTHxHEIGHT]
+  aoc run [--day N]... [--timeout SECONDS] [--max-steps N]";
+
+#[derive(Debug)]
+struct BenchArgs {
+    days: Vec<u32>,
+    json: PathBuf,
+    baseline: Option<PathBuf>,
+    threshold: f64,
+}
+
+impl BenchArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut bench = Self {
+            days: vec![],
+            json: PathBuf::from("target/bench-report.json"),
+            baseline: None,
+            threshold: 10.0,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(miette!("{arg} needs a value\n{USAGE}"));
+            match arg.as_str() {
+                "--day" => bench.days.push(value()?.parse().into_diagnostic()?),
+                "--json" => bench.json = value()?.into(),
+                "--baseline" => bench.baseline = Some(value()?.into()),
+                "--threshold" => bench.threshold = value()?.parse().into_diagnostic()?,
+                _ => return Err(miette!("Unknown argument {arg}\n{USAGE}")),
+            }
+        }
+
+        Ok(bench)
+    }
+}
+
+fn bench(args: BenchArgs) -> Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let days = if args.days.is_empty() { bench_days(root)? } else { args.days };
+
+    let mut report = Report::default();
+    for day in days {
+        eprintln!("Benching day {day}");
+        report.days.insert(day, run_day(root, day)?);
+    }
+    report.save(&root.join(&args.json))?;
+
+    let baseline = args.baseline.map(|path| Report::load(&root.join(path))).transpose()?;
+    println!("{}", report.table(baseline.as_ref()));
+    println!("Saved to {}", args.json.display());
+
+    let regressions = baseline.map_or(vec![], |baseline| report.regressions(&baseline, args.threshold));
+    if !regressions.is_empty() {
+        regressions.iter().for_each(|r| println!("Day {} {} is {:.1}% slower", r.day, r.bench, r.change));
+        return Err(miette!("{} benches regressed by more than {}%", regressions.len(), args.threshold));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct GenArgs {
+    day: u32,
+    size: Option<usize>,
+    seed: u64,
+    arena: (i64, i64),
+}
+
+impl GenArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut day = None;
+        let mut generate = Self { day: 0, size: None, seed: 2024, arena: (101, 103) };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(miette!("{arg} needs a value\n{USAGE}"));
+            match arg.as_str() {
+                "--day" => day = Some(value()?.parse().into_diagnostic()?),
+                "--size" => generate.size = Some(value()?.parse().into_diagnostic()?),
+                "--seed" => generate.seed = value()?.parse().into_diagnostic()?,
+                "--arena" => {
+                    let arena = value()?;
+                    let (width, height) = arena.split_once('x').ok_or(miette!("Arena {arena:?} isn't WIDTHxHEIGHT"))?;
+                    generate.arena = (width.parse().into_diagnostic()?, height.parse().into_diagnostic()?);
+                },
+                _ => return Err(miette!("Unknown argument {arg}\n{USAGE}")),
+            }
+        }
+        generate.day = day.ok_or(miette!("--day is required\n{USAGE}"))?;
+
+        Ok(generate)
+    }
+}
+
+fn generate(args: GenArgs) -> Result<()> {
+    let (unit, default) = gen::size_of(args.day).ok_or(miette!("There is no generator for day {}", args.day))?;
+    let size = args.size.unwrap_or(default);
+    let generated = gen::generate(args.day, args.seed, &gen::Options { size, arena: args.arena })?;
+
+    print!("{}", generated.input);
+    eprintln!("Day {} input with {size} {unit}, seed {}", args.day, args.seed);
+    if let Some(answer) = generated.answer {
+        eprintln!("Expected part 2 answer: {answer}");
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct RunArgs {
+    days: Vec<u32>,
+    limits: Limits,
+}
+
+impl RunArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut run = Self { days: vec![], limits: Limits { timeout: None, max_steps: None } };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(miette!("{arg} needs a value\n{USAGE}"));
+            match arg.as_str() {
+                "--day" => run.days.push(value()?.parse().into_diagnostic()?),
+                "--timeout" => run.limits.timeout = Some(value()?.parse().into_diagnostic()?),
+                "--max-steps" => run.limits.max_steps = Some(value()?.parse().into_diagnostic()?),
+                _ => return Err(miette!("Unknown argument {arg}\n{USAGE}")),
+            }
+        }
+
+        Ok(run)
+    }
+}
+
+fn run(args: RunArgs) -> Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let days = if args.days.is_empty() { run::solution_days(root)? } else { args.days };
+    run::build(root)?;
+
+    // Ctrl-C cancels the day that is running, the others still run
+    budget::cancel_on_interrupt();
+    let mut runs = vec![];
+    for day in days {
+        eprintln!("Running day {day}");
+        runs.push(run::run_day(root, day, &args.limits)?);
+    }
+    println!("{}", run::summary(&runs));
+
+    let unfinished = runs.iter().filter(|run| run.status != Status::Finished).count();
+    if unfinished > 0 {
+        return Err(miette!("{unfinished} days didn't finish"));
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("bench") => bench(BenchArgs::parse(args)?),
+        Some("gen") => generate(GenArgs::parse(args)?),
+        Some("run") => run(RunArgs::parse(args)?),
+        _ => Err(miette!("{USAGE}")),
+    }
+}
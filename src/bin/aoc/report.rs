@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, fmt::Write, path::Path, process::Command};
+use miette::{miette, IntoDiagnostic, Result};
+use serde::{Deserialize, Serialize};
+
+/// Timing of one bench in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+}
+
+/// Timings per day and bench, the bench name without its `bench_` prefix
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub days: BTreeMap<u32, BTreeMap<String, Timing>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u32,
+    pub bench: String,
+    /// Change of the median in percent
+    pub change: f64,
+}
+
+/// Nanoseconds in a divan duration such as `42.87 µs`
+fn parse_duration(value: &str, unit: &str) -> Result<f64> {
+    let scale = match unit {
+        "ps" => 1e-3,
+        "ns" => 1.0,
+        "µs" | "us" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        "m" => 60e9,
+        "h" => 3600e9,
+        "d" => 86400e9,
+        _ => return Err(miette!("Unknown time unit {unit:?}")),
+    };
+
+    Ok(value.parse::<f64>().into_diagnostic()? * scale)
+}
+
+/// Benches from divan's terminal table, rows look like `├─ bench_part1  1.2 ms │ 2.3 ms │ 1.5 ms │ …`
+pub fn parse_divan(output: &str) -> Result<BTreeMap<String, Timing>> {
+    output.lines()
+        .map(|line| line.trim_start_matches(['│', ' ']))
+        .filter(|line| line.starts_with("├─") || line.starts_with("╰─"))
+        .map(|line| {
+            let columns = line.split('│').map(str::split_whitespace).map(Iterator::collect::<Vec<_>>).collect::<Vec<_>>();
+            let (Some([_, name, min @ ..]), Some(max), Some(median)) = (columns.first().map(Vec::as_slice), columns.get(1), columns.get(2)) else {
+                return Err(miette!("Unexpected divan row {line:?}"));
+            };
+            let duration = |column: &[&str]| match column {
+                [value, unit] => parse_duration(value, unit),
+                _ => Err(miette!("Unexpected divan duration {column:?} in {line:?}")),
+            };
+
+            Ok((name.trim_start_matches("bench_").to_string(), Timing {
+                min: duration(min)?,
+                median: duration(median)?,
+                max: duration(max)?,
+            }))
+        })
+        .collect()
+}
+
+/// Days that have a `bench.rs` next to their solution
+pub fn bench_days(root: &Path) -> Result<Vec<u32>> {
+    let mut days = std::fs::read_dir(root.join("src/bin")).into_diagnostic()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("bench.rs").exists())
+        .filter_map(|entry| entry.file_name().to_str()?.strip_prefix("day")?.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+
+    Ok(days)
+}
+
+/// Run `cargo bench` for one day and read its timings
+pub fn run_day(root: &Path, day: u32) -> Result<BTreeMap<String, Timing>> {
+    let cargo = std::env::var("CARGO").unwrap_or("cargo".to_string());
+    let output = Command::new(cargo)
+        .current_dir(root)
+        .args(["bench", "--bench", &format!("day{day}_bench")])
+        .output()
+        .into_diagnostic()?;
+    if !output.status.success() {
+        return Err(miette!("Benches of day {day} failed:\n{}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    parse_divan(&String::from_utf8_lossy(&output.stdout))
+}
+
+impl Report {
+    pub fn load(path: &Path) -> Result<Self> {
+        serde_json::from_str(&std::fs::read_to_string(path).into_diagnostic()?).into_diagnostic()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).into_diagnostic()?).into_diagnostic()
+    }
+
+    /// Benches whose median got slower than the baseline by more than `threshold` percent
+    pub fn regressions(&self, baseline: &Report, threshold: f64) -> Vec<Regression> {
+        self.days.iter()
+            .flat_map(|(day, benches)| benches.iter().map(move |(bench, timing)| (*day, bench, timing)))
+            .filter_map(|(day, bench, timing)| {
+                let before = baseline.days.get(&day)?.get(bench)?;
+                let change = (timing.median / before.median - 1.0) * 100.0;
+                (change > threshold).then(|| Regression { day, bench: bench.clone(), change })
+            })
+            .collect()
+    }
+
+    /// One row per bench, with the change against the baseline when there is one
+    pub fn table(&self, baseline: Option<&Report>) -> String {
+        let mut table = format!("{:<5} {:<14} {:>10} {:>10} {:>10} {:>9}\n", "day", "bench", "min", "median", "max", "change");
+        self.days.iter().for_each(|(day, benches)| benches.iter().for_each(|(bench, timing)| {
+            let change = baseline
+                .and_then(|baseline| baseline.days.get(day)?.get(bench))
+                .map_or(String::new(), |before| format!("{:+.1}%", (timing.median / before.median - 1.0) * 100.0));
+            writeln!(
+                table,
+                "{:<5} {:<14} {:>10} {:>10} {:>10} {:>9}",
+                day,
+                bench,
+                format_duration(timing.min),
+                format_duration(timing.median),
+                format_duration(timing.max),
+                change,
+            ).unwrap();
+        }));
+
+        table
+    }
+}
+
+pub fn format_duration(nanos: f64) -> String {
+    match nanos {
+        n if n < 1e3 => format!("{n:.1} ns"),
+        n if n < 1e6 => format!("{:.2} µs", n / 1e3),
+        n if n < 1e9 => format!("{:.2} ms", n / 1e6),
+        n => format!("{:.2} s", n / 1e9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use super::{format_duration, parse_divan, Regression, Report, Timing};
+
+    const OUTPUT: &str = "Timer precision: 20 ns
+day9_bench            fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ bench_parse_part1  41.7 µs       │ 95.37 µs      │ 42.87 µs      │ 44.5 µs       │ 100     │ 100
+├─ bench_part1        1.2 ms        │ 2.3 ms        │ 1.5 ms        │ 1.6 ms        │ 100     │ 100
+╰─ bench_part2        900 ns        │ 1.1 s         │ 950 ns        │ 1 µs          │ 100     │ 100";
+
+    fn timing(min: f64, median: f64, max: f64) -> Timing {
+        Timing { min, median, max }
+    }
+
+    #[test]
+    fn test_parse_divan() {
+        let benches = parse_divan(OUTPUT).unwrap();
+        assert_eq!(benches, BTreeMap::from([
+            ("parse_part1".to_string(), timing(41_700.0, 42_870.0, 95_370.0)),
+            ("part1".to_string(), timing(1_200_000.0, 1_500_000.0, 2_300_000.0)),
+            ("part2".to_string(), timing(900.0, 950.0, 1_100_000_000.0)),
+        ]));
+        assert!(parse_divan("├─ bench_part1  1.2 fortnights │ 2 ms │ 1 ms").is_err());
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = Report { days: BTreeMap::from([(9, parse_divan(OUTPUT).unwrap())]) };
+        let mut current = baseline.clone();
+        current.days.get_mut(&9).unwrap().insert("part1".to_string(), timing(1.0, 1_800_000.0, 1.0));
+        current.days.get_mut(&9).unwrap().insert("part2".to_string(), timing(1.0, 1_000.0, 1.0));
+        current.days.insert(10, BTreeMap::from([("part1".to_string(), timing(1.0, 1.0, 1.0))]));
+
+        assert_eq!(current.regressions(&baseline, 10.0), vec![Regression { day: 9, bench: "part1".to_string(), change: 19.999999999999996 }]);
+        assert_eq!(current.regressions(&baseline, 25.0), vec![]);
+        assert!(current.table(Some(&baseline)).lines().any(|line| line.starts_with("9     part1") && line.ends_with("+20.0%")));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let report = Report { days: BTreeMap::from([(9, parse_divan(OUTPUT).unwrap())]) };
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with(r#"{"days":{"9":{"parse_part1":{"min":41700.0"#));
+        assert_eq!(serde_json::from_str::<Report>(&json).unwrap(), report);
+        assert_eq!(format_duration(42_870.0), "42.87 µs");
+    }
+}
@@ -0,0 +1,199 @@
+use std::{fmt::Write, io::Read, path::Path, process::{Child, Command, Stdio}, thread, time::{Duration, Instant}};
+use miette::{miette, IntoDiagnostic, Result};
+use crate::budget::{self, MAX_STEPS_VAR, TIMEOUT_VAR};
+use crate::report::format_duration;
+
+/// Time a day gets on top of its part timeouts before it is killed, for parsing and start up
+const GRACE: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Seconds each part may run for
+    pub timeout: Option<f64>,
+    pub max_steps: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Finished,
+    Failed(String),
+    /// Didn't stop by itself within the part timeouts and the grace period
+    Killed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayRun {
+    pub day: u32,
+    pub parts: Vec<String>,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+/// Days that have a `main.rs`
+pub fn solution_days(root: &Path) -> Result<Vec<u32>> {
+    let mut days = std::fs::read_dir(root.join("src/bin")).into_diagnostic()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("main.rs").exists())
+        .filter_map(|entry| entry.file_name().to_str()?.strip_prefix("day")?.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+
+    Ok(days)
+}
+
+/// Build every day once, so the timeouts don't include compiling
+pub fn build(root: &Path) -> Result<()> {
+    let cargo = std::env::var("CARGO").unwrap_or("cargo".to_string());
+    let status = Command::new(cargo)
+        .current_dir(root)
+        .args(["build", "--release", "--bins"])
+        .status()
+        .into_diagnostic()?;
+    if !status.success() {
+        return Err(miette!("Building the days failed"));
+    }
+
+    Ok(())
+}
+
+/// Answers from the `Part N: …` lines, errors shortened to their message
+pub fn parse_parts(output: &str) -> Vec<String> {
+    let mut lines = output.lines();
+    let mut parts = vec![];
+    while let Some(line) = lines.next() {
+        let Some((_, result)) = line.split_once(": ").filter(|(part, _)| part.starts_with("Part ")) else { continue };
+        if let Some(answer) = result.strip_prefix("Ok(").and_then(|answer| answer.strip_suffix(')')) {
+            parts.push(answer.trim_matches('"').to_string());
+        } else if result.starts_with("Err(") {
+            // miette renders the error over several lines, the message is the one marked with ×
+            let message = lines.by_ref()
+                .take_while(|line| *line != ")")
+                .find_map(|line| line.trim().strip_prefix("× "))
+                .unwrap_or("error");
+            parts.push(format!("error: {message}"));
+        } else {
+            parts.push(result.to_string());
+        }
+    }
+
+    parts
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        let _ = pipe.read_to_string(&mut output);
+        output
+    })
+}
+
+/// Wait for the day to exit, killing it once it overstays the deadline
+fn wait(child: &mut Child, deadline: Option<Instant>) -> Result<Status> {
+    let mut cancelled = None;
+    loop {
+        if let Some(exit) = child.try_wait().into_diagnostic()? {
+            return Ok(match () {
+                _ if cancelled.is_some() => Status::Cancelled,
+                _ if exit.success() => Status::Finished,
+                _ => Status::Failed(exit.to_string()),
+            });
+        }
+        // Ctrl-C reaches the day as well, it gets the grace period to stop its part
+        if cancelled.is_none() && budget::interrupted() {
+            budget::clear_interrupt();
+            cancelled = Some(Instant::now() + GRACE);
+        }
+        let killed_at = cancelled.into_iter().chain(deadline).min();
+        if killed_at.is_some_and(|killed_at| Instant::now() > killed_at) {
+            child.kill().into_diagnostic()?;
+            child.wait().into_diagnostic()?;
+            return Ok(if cancelled.is_some() { Status::Cancelled } else { Status::Killed });
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Run the release binary of one day within the limits
+pub fn run_day(root: &Path, day: u32, limits: &Limits) -> Result<DayRun> {
+    let mut command = Command::new(root.join(format!("target/release/day{day}")));
+    // Only the last line of stderr is shown, so there is no one to draw progress for
+    command.current_dir(root).stdout(Stdio::piped()).stderr(Stdio::piped()).env("AOC_PROGRESS", "off");
+    if let Some(timeout) = limits.timeout {
+        command.env(TIMEOUT_VAR, timeout.to_string());
+    }
+    if let Some(max_steps) = limits.max_steps {
+        command.env(MAX_STEPS_VAR, max_steps.to_string());
+    }
+
+    let started = Instant::now();
+    let mut child = command.spawn().map_err(|e| miette!("Failed to start day {day}: {e}"))?;
+    let stdout = read_in_background(child.stdout.take().ok_or(miette!("Day {day} has no stdout"))?);
+    let stderr = read_in_background(child.stderr.take().ok_or(miette!("Day {day} has no stderr"))?);
+    let deadline = limits.timeout.map(|timeout| started + Duration::from_secs_f64(timeout * 2.0) + GRACE);
+    let status = match wait(&mut child, deadline)? {
+        Status::Failed(exit) => {
+            let stderr = stderr.join().map_err(|_| miette!("Reading the errors of day {day} failed"))?;
+            Status::Failed(stderr.lines().rev().find(|line| !line.trim().is_empty()).map_or(exit, |line| line.trim().to_string()))
+        },
+        status => status,
+    };
+    let output = stdout.join().map_err(|_| miette!("Reading the output of day {day} failed"))?;
+
+    Ok(DayRun { day, parts: parse_parts(&output), status, elapsed: started.elapsed() })
+}
+
+/// One row per day with both answers, parts that never printed shown as `-`
+pub fn summary(runs: &[DayRun]) -> String {
+    let mut table = format!("{:<5} {:<30} {:<30} {:>10}  {}\n", "day", "part 1", "part 2", "time", "status");
+    runs.iter().for_each(|run| {
+        let part = |index: usize| run.parts.get(index).map_or("-", String::as_str);
+        let status = match &run.status {
+            Status::Finished => "finished".to_string(),
+            Status::Failed(reason) => format!("failed: {reason}"),
+            Status::Killed => "killed".to_string(),
+            Status::Cancelled => "cancelled".to_string(),
+        };
+        writeln!(
+            table,
+            "{:<5} {:<30} {:<30} {:>10}  {}",
+            run.day,
+            part(0),
+            part(1),
+            format_duration(run.elapsed.as_nanos() as f64),
+            status,
+        ).unwrap();
+    });
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{parse_parts, summary, DayRun, Status};
+
+    const OUTPUT: &str = "Part 1: Ok(\"4,6,3,5,6,3,5,2,1,0\")
+Part 2: Err(budget::exhausted
+
+  × Timed out after 200ms
+)";
+
+    #[test]
+    fn test_parse_parts() {
+        assert_eq!(parse_parts(OUTPUT), vec!["4,6,3,5,6,3,5,2,1,0", "error: Timed out after 200ms"]);
+        assert_eq!(parse_parts("0\nPart 1: Ok(11)\n1000000\nPart 2: Ok(31)"), vec!["11", "31"]);
+    }
+
+    #[test]
+    fn test_summary() {
+        let runs = [
+            DayRun { day: 1, parts: vec!["11".to_string(), "31".to_string()], status: Status::Finished, elapsed: Duration::from_millis(3) },
+            DayRun { day: 17, parts: vec!["1".to_string()], status: Status::Cancelled, elapsed: Duration::from_secs(2) },
+        ];
+        let table = summary(&runs);
+        assert!(table.lines().any(|line| line.starts_with("1     11") && line.ends_with("3.00 ms  finished")));
+        assert!(table.lines().any(|line| line.starts_with("17    1     ") && line.contains(" -  ") && line.ends_with("cancelled")));
+    }
+}
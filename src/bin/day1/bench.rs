@@ -0,0 +1,26 @@
+mod solution;
+use solution::Day1;
+use solution::Solution;
+
+const INPUT: &str = include_str!("../../../inputs/day1.txt");
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day1::parse(divan::black_box(INPUT)));
+}
+
+#[divan::bench]
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day1::parse(INPUT).unwrap();
+    bencher.bench(|| Day1::part1(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day1::parse(INPUT).unwrap();
+    bencher.bench(|| Day1::part2(divan::black_box(&parsed)));
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap};
+use std::iter::zip;
+use miette::{miette, LabeledSpan, Result};
+
+/// Whitespace separated numeric columns, every bad line is reported in one diagnostic
+pub fn parse_columns(input: &str, count: usize) -> Result<Vec<Vec<i64>>> {
+    let mut columns = vec![vec![]; count];
+    let mut labels = vec![];
+    let mut offset = 0;
+    for (number, line) in input.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        if words.len() != count {
+            labels.push(LabeledSpan::at(start..start + line.len(), format!("line {} has {} columns, expected {count}", number + 1, words.len())));
+            continue;
+        }
+        match words.iter().map(|word| word.parse::<i64>()).collect::<Result<Vec<_>, _>>() {
+            Ok(values) => zip(columns.iter_mut(), values).for_each(|(column, value)| column.push(value)),
+            Err(error) => labels.push(LabeledSpan::at(start..start + line.len(), format!("line {}: {error}", number + 1))),
+        }
+    }
+
+    if !labels.is_empty() {
+        let count = labels.len();
+        return Err(miette!(
+            code = "day1::columns",
+            labels = labels,
+            "{count} bad lines in the location lists",
+        ).with_source_code(input.to_string()));
+    }
+
+    Ok(columns)
+}
+
+/// Compares columns of location ids, columns are addressed by index
+#[derive(Debug, Clone)]
+pub struct Reconciliation {
+    sorted: Vec<Vec<i64>>,
+}
+
+impl Reconciliation {
+    pub fn new(columns: Vec<Vec<i64>>) -> Self {
+        let mut sorted = columns;
+        sorted.iter_mut().for_each(|column| column.sort_unstable());
+        Self { sorted }
+    }
+
+    fn column(&self, index: usize) -> Result<&[i64]> {
+        self.sorted.get(index).map(Vec::as_slice)
+            .ok_or_else(|| miette!("There is no column {index}, the lists have {} columns", self.sorted.len()))
+    }
+
+    /// Differences between the smallest values of both columns, then the next smallest and so on
+    fn differences(&self, a: usize, b: usize) -> Result<impl Iterator<Item = u64> + '_> {
+        Ok(zip(self.column(a)?, self.column(b)?).map(|(x, y)| x.abs_diff(*y)))
+    }
+
+    /// Sum of the differences
+    pub fn distance(&self, a: usize, b: usize) -> Result<u64> {
+        self.differences(a, b)?.try_fold(0u64, |sum, difference| sum.checked_add(difference))
+            .ok_or(miette!("The total distance doesn't fit in 64 bits"))
+    }
+
+    /// Every value of `a` times how often it appears in `b`
+    pub fn similarity(&self, a: usize, b: usize) -> Result<i64> {
+        let mut counts: HashMap<i64, i64> = HashMap::new();
+        self.column(b)?.iter().for_each(|value| *counts.entry(*value).or_default() += 1);
+        self.column(a)?.iter().try_fold(0i64, |sum, value| sum.checked_add(value.checked_mul(*counts.get(value).unwrap_or(&0))?))
+            .ok_or(miette!("The similarity score doesn't fit in 64 bits"))
+    }
+
+    /// How many sorted pairs differ by each distance
+    pub fn histogram(&self, a: usize, b: usize) -> Result<BTreeMap<u64, usize>> {
+        let mut histogram = BTreeMap::new();
+        self.differences(a, b)?.for_each(|difference| *histogram.entry(difference).or_default() += 1);
+        Ok(histogram)
+    }
+
+    /// Values of `a` left over after pairing every value with an equal one in `b`, sorted
+    pub fn unmatched(&self, a: usize, b: usize) -> Result<Vec<i64>> {
+        let (mut i, mut j) = (0, 0);
+        let (left, right) = (self.column(a)?, self.column(b)?);
+        let mut unmatched = vec![];
+        while i < left.len() {
+            match right.get(j).map(|value| left[i].cmp(value)) {
+                Some(std::cmp::Ordering::Equal) => {
+                    i += 1;
+                    j += 1;
+                },
+                Some(std::cmp::Ordering::Greater) => j += 1,
+                _ => {
+                    unmatched.push(left[i]);
+                    i += 1;
+                },
+            }
+        }
+
+        Ok(unmatched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use std::collections::BTreeMap;
+    #[allow(unused_imports)]
+    use super::{parse_columns, Reconciliation};
+
+    #[test]
+    fn test_parse_reports_bad_lines() {
+        assert_eq!(parse_columns("1 2 3\n4 5 6\n\n", 3).unwrap(), vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+
+        let error = parse_columns("1 2\n3\n4 x\n5 6", 2).unwrap_err();
+        assert_eq!(error.to_string(), "2 bad lines in the location lists");
+        let labels = error.labels().unwrap().map(|l| (l.offset(), l.label().unwrap().to_string())).collect::<Vec<_>>();
+        assert_eq!(labels, vec![
+            (4, "line 2 has 1 columns, expected 2".to_string()),
+            (6, "line 3: invalid digit found in string".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_reconciliation() {
+        let columns = parse_columns("3   4\n4   3\n2   5\n1   3\n3   9\n3   3", 2).unwrap();
+        let lists = Reconciliation::new(columns);
+        assert_eq!(lists.distance(0, 1).unwrap(), 11);
+        assert_eq!(lists.similarity(0, 1).unwrap(), 31);
+        assert_eq!(lists.histogram(0, 1).unwrap(), BTreeMap::from([(0, 1), (1, 2), (2, 2), (5, 1)]));
+        assert_eq!(lists.unmatched(0, 1).unwrap(), vec![1, 2]);
+        assert_eq!(lists.unmatched(1, 0).unwrap(), vec![5, 9]);
+    }
+
+    #[test]
+    fn test_more_columns() {
+        let lists = Reconciliation::new(parse_columns("1 2 -3\n5 2 1", 3).unwrap());
+        assert_eq!(lists.distance(0, 2).unwrap(), 4 + 4);
+        assert_eq!(lists.distance(1, 2).unwrap(), 5 + 1);
+        assert_eq!(lists.similarity(1, 1).unwrap(), 8);
+        assert_eq!(lists.unmatched(0, 2).unwrap(), vec![5]);
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let lists = Reconciliation::new(parse_columns("1 2
+3 4", 2).unwrap());
+        assert_eq!(lists.distance(0, 2).unwrap_err().to_string(), "There is no column 2, the lists have 2 columns");
+        assert!(lists.similarity(5, 0).is_err());
+        assert!(lists.histogram(0, 2).is_err());
+        assert!(lists.unmatched(2, 0).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "columns.rs"]
+pub mod columns;
+
+pub use utils::Solution;
+use columns::{parse_columns, Reconciliation};
+use miette::{miette, Result};
+use std::fmt::{self, Display};
+pub struct Day1;
+
+/// How many sorted pairs are each distance apart
+struct DistancesView<'a> {
+    lists: &'a Reconciliation,
+}
+
+impl Display for DistancesView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (distance, count) in self.lists.histogram(0, 1).map_err(|_| fmt::Error)? {
+            writeln!(f, "{distance:>8}: {count}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Location ids of the left list that no id of the right list pairs up with
+struct UnmatchedView<'a> {
+    lists: &'a Reconciliation,
+}
+
+impl Display for UnmatchedView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unmatched = self.lists.unmatched(0, 1).map_err(|_| fmt::Error)?;
+        writeln!(f, "{} ids: {unmatched:?}", unmatched.len())
+    }
+}
+
+impl Solution<i64> for Day1 {
+    type Parsed = Reconciliation;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(Reconciliation::new(parse_columns(input, 2)?))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(lists: &Self::Parsed) -> Result<i64> {
+        progress::picture("distances", DistancesView { lists });
+        i64::try_from(lists.distance(0, 1)?).map_err(|_| miette!("The total distance doesn't fit in 64 bits"))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2(lists: &Self::Parsed) -> Result<i64> {
+        progress::picture("unmatched", UnmatchedView { lists });
+        lists.similarity(0, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Day1, Solution};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"3   4
+4   3
+2   5
+1   3
+3   9
+3   3"#], super::fuzz::solve::<Day1, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        let lists = Day1::parse("-9223372036854775808 9223372036854775807\n9223372036854775807 9223372036854775807").unwrap();
+        assert!(Day1::part1(&lists).is_err());
+        assert!(Day1::part2(&lists).is_err());
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"3   4
+4   3
+2   5
+1   3
+3   9
+3   3"#;
+        let result = Day1::part1(&Day1::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 11)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"3   4
+4   3
+2   5
+1   3
+3   9
+3   3"#;
+        let result = Day1::part2(&Day1::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 31)
+    }
+}
@@ -1,9 +1,19 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
 
 use glam::IVec2;
 pub use utils::Solution;
-use miette::Result;
+use miette::{miette, Result};
 use itertools::Itertools;
 pub struct Day10;
 
@@ -33,7 +43,7 @@ impl Direction {
     }
 }
 
-pub fn parse(input: &str) -> (Vec<Vec<u32>>, Vec<IVec2>) {
+pub fn parse(input: &str) -> Result<(Vec<Vec<u32>>, Vec<IVec2>)> {
     let mut starts = vec![];
     let map = input.lines().enumerate()
     .map(|(y, l)| l.chars().enumerate()
@@ -41,10 +51,14 @@ pub fn parse(input: &str) -> (Vec<Vec<u32>>, Vec<IVec2>) {
             if n == '0' {
                 starts.push(IVec2::new(x as i32, y as i32));
             }
-            n.to_digit(10).unwrap()
-        }).collect()).collect();
+            n.to_digit(10).ok_or(miette!("Height {n:?} at line {}, column {} isn't a digit", y + 1, x + 1))
+        }).collect::<Result<Vec<_>>>()).collect::<Result<Vec<_>>>()?;
+
+    if let Some((y, row)) = map.iter().find_position(|row| row.len() != map[0].len()) {
+        return Err(miette!("Line {} is {} wide, the map is {} wide", y + 1, row.len(), map[0].len()));
+    }
 
-    (map, starts)
+    Ok((map, starts))
 }
 
 pub fn find_path(map: &Vec<Vec<u32>>, start_pos: &IVec2, current_num: u32, goals: &mut Vec<IVec2>) {
@@ -65,25 +79,30 @@ pub fn find_path(map: &Vec<Vec<u32>>, start_pos: &IVec2, current_num: u32, goals
 }
 
 impl Solution<u32> for Day10 {
-    fn part1(input: &str) -> Result<u32> {
-        let (map, starts) = parse(input);
+    type Parsed = (Vec<Vec<u32>>, Vec<IVec2>);
 
-        Ok(starts.into_iter()
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse(input)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1((map, starts): &Self::Parsed) -> Result<u32> {
+        Ok(starts.iter()
         .map(|start| {
             let mut goals: Vec<IVec2> = vec![];
-            find_path(&map, &start, 0, &mut goals);
+            find_path(map, start, 0, &mut goals);
             goals.into_iter().unique().count() as u32
         })
         .sum::<u32>())
     }
 
-    fn part2(input: &str) -> Result<u32> {
-        let (map, starts) = parse(input);
-
-        Ok(starts.into_iter()
+    #[tracing::instrument(skip_all)]
+    fn part2((map, starts): &Self::Parsed) -> Result<u32> {
+        Ok(starts.iter()
         .map(|start| {
             let mut goals: Vec<IVec2> = vec![];
-            find_path(&map, &start, 0, &mut goals);
+            find_path(map, start, 0, &mut goals);
             goals.len() as u32
         })
         .sum::<u32>())
@@ -95,13 +114,27 @@ mod tests {
     #[allow(unused_imports)]
     use super::{Day10, Solution};
 
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"0123
+1234
+8765
+9876"#], super::fuzz::solve::<Day10, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day10::parse("03\n1").is_err());
+        assert!(Day10::parse("0.").is_err());
+    }
+
     #[test]
     fn test_part1_small() {
         let test = r#"0123
 1234
 8765
 9876"#;
-        let result = Day10::part1(test);
+        let result = Day10::part1(&Day10::parse(test).unwrap());
         assert_eq!(result.unwrap(), 1)
     }
 
@@ -115,7 +148,7 @@ mod tests {
 32019012
 01329801
 10456732"#;
-        let result = Day10::part1(test);
+        let result = Day10::part1(&Day10::parse(test).unwrap());
         assert_eq!(result.unwrap(), 36)
     }
 
@@ -127,7 +160,7 @@ mod tests {
 345678
 416789
 567891"#;
-        let result = Day10::part2(test);
+        let result = Day10::part2(&Day10::parse(test).unwrap());
         assert_eq!(result.unwrap(), 227)
     }
 
@@ -141,7 +174,7 @@ mod tests {
 32019012
 01329801
 10456732"#;
-        let result = Day10::part2(test);
+        let result = Day10::part2(&Day10::parse(test).unwrap());
         assert_eq!(result.unwrap(), 81)
     }
 }
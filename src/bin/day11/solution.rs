@@ -1,9 +1,23 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../property.rs"]
+mod property;
 pub use utils::Solution;
 
 use std::collections::HashMap;
-use miette::Result;
+use miette::{miette, IntoDiagnostic, Result};
 pub struct Day11;
 
 const PART_1_BLINKS: u64 = 25;
@@ -28,68 +42,129 @@ impl NumLenSplit for u64 {
 }
 
 
-pub fn solve(stone: u64, n: u64, max_iterations: &u64, cache: &mut HashMap<(u64, u64), u64>) -> u64 {
+/// Stones after the blinks, `None` once a stone no longer fits in 64 bits
+pub fn solve(stone: u64, n: u64, max_iterations: &u64, cache: &mut HashMap<(u64, u64), u64>) -> Option<u64> {
     if max_iterations == &n {
-        return 1;
+        return Some(1);
     } else if let Some(cache_value ) = cache.get(&(stone, n)) {
-        return *cache_value;
+        return Some(*cache_value);
     }
 
     let result = match stone {
-        0 => solve(1, n + 1, max_iterations, cache),
+        0 => solve(1, n + 1, max_iterations, cache)?,
         num if num.is_even_length()  => {
             let (left, right) = num.split();
-            solve(left, n + 1, max_iterations, cache) + solve(right, n + 1, max_iterations, cache)
+            solve(left, n + 1, max_iterations, cache)?.checked_add(solve(right, n + 1, max_iterations, cache)?)?
         },
-        _ => solve(stone * 2024, n + 1, max_iterations, cache),
+        _ => solve(stone.checked_mul(2024)?, n + 1, max_iterations, cache)?,
     };
 
     // Save result to the cache
     cache.insert((stone, n), result);
 
-    result
+    Some(result)
+}
+
+fn blink(stones: &[u64], blinks: &u64) -> Result<u64> {
+    let mut cache = HashMap::new();
+
+    stones.iter()
+        .map(|stone| solve(*stone, 0, blinks, &mut cache))
+        .try_fold(0u64, |total, count| total.checked_add(count?))
+        .ok_or(miette!("The stones no longer fit in 64 bits"))
 }
 
 impl Solution<u64> for Day11 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u64> {
-        let stones = input
+    type Parsed = Vec<u64>;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        input
             .split_whitespace()
-            .map(|line| line.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
-        let mut cache = HashMap::new();
+            .map(|line| line.parse::<u64>().into_diagnostic())
+            .collect()
+    }
 
-        Ok(stones.into_iter().map(|stone| solve(stone, 0, &PART_1_BLINKS, &mut cache)).sum())
+    #[tracing::instrument(skip_all)]
+    fn part1(stones: &Self::Parsed) -> Result<u64> {
+        blink(stones, &PART_1_BLINKS)
     }
     
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u64> {
-        let stones = input
-            .split_whitespace()
-            .map(|line| line.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
-        let mut cache = HashMap::new();
-
-        Ok(stones.into_iter().map(|stone| solve(stone, 0, &PART_2_BLINKS, &mut cache)).sum())
+    #[tracing::instrument(skip_all)]
+    fn part2(stones: &Self::Parsed) -> Result<u64> {
+        blink(stones, &PART_2_BLINKS)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    #[allow(unused_imports)]
+    use std::collections::HashMap;
     #[allow(unused_imports)]
     use super::{Day11, Solution};
+    #[allow(unused_imports)]
+    use super::property::check;
+    #[allow(unused_imports)]
+    use super::{solve, NumLenSplit};
+
+    /// Blink every stone one at a time, exponential in the number of blinks
+    #[allow(dead_code)]
+    fn blink_naive(stones: &[u64], blinks: u64) -> u64 {
+        let mut stones = stones.to_vec();
+        for _ in 0..blinks {
+            stones = stones.into_iter().flat_map(|stone| match stone {
+                0 => vec![1],
+                _ if stone.to_string().len() % 2 == 0 => {
+                    let digits = stone.to_string();
+                    let (left, right) = digits.split_at(digits.len() / 2);
+                    vec![left.parse().unwrap(), right.parse().unwrap()]
+                },
+                _ => vec![stone * 2024],
+            }).collect();
+        }
+
+        stones.len() as u64
+    }
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"125 17"#], super::fuzz::part1::<Day11, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day11::part2(&Day11::parse("6545126279117").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(1000u64.split(), (10, 0));
+        assert_eq!(253000u64.split(), (253, 0));
+        assert!(!7u64.is_even_length());
+    }
+
+    #[test]
+    fn test_solve_matches_naive() {
+        check(
+            |rng| ((0..rng.range(1..=4)).map(|_| rng.range(0..=1_000_000) as u64).collect::<Vec<_>>(), rng.range(0..=12) as u64),
+            |(stones, blinks)| {
+                let mut cache = HashMap::new();
+                stones.iter().map(|stone| solve(*stone, 0, blinks, &mut cache).unwrap()).sum::<u64>() == blink_naive(stones, *blinks)
+            },
+        );
+    }
 
     #[test]
     fn test_part1() {
         let test = r#"125 17"#;
-        let result = Day11::part1(test);
+        let result = Day11::part1(&Day11::parse(test).unwrap());
         assert_eq!(result.unwrap(), 55312)
     }
 
     #[test]
     fn test_part2() {
         let test = r#"125 17"#;
-        let result = Day11::part2(test);
+        let result = Day11::part2(&Day11::parse(test).unwrap());
         assert_eq!(result.unwrap(), 65601038650482)
     }
 }
@@ -1,22 +1,26 @@
 mod solution;
 use solution::Day12;
 use solution::Solution;
-use miette::Result;
+
+const INPUT: &str = include_str!("../../../inputs/day12.txt");
 
 fn main() {
     divan::main();
 }
 
 #[divan::bench]
-fn bench_part1() -> Result<u32> {
-   Day12::part1(divan::black_box(include_str!(
-        "../../../inputs/day12.txt",
-    )))
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day12::parse(divan::black_box(INPUT)));
 }
 
 #[divan::bench]
-fn bench_part2() -> Result<u32> {
-    Day12::part2(divan::black_box(include_str!(
-        "../../../inputs/day12.txt",
-    )))
-}
\ No newline at end of file
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day12::parse(INPUT).unwrap();
+    bencher.bench(|| Day12::part1(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day12::parse(INPUT).unwrap();
+    bencher.bench(|| Day12::part2(divan::black_box(&parsed)));
+}
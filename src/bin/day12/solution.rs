@@ -1,9 +1,23 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../property.rs"]
+mod property;
 pub use utils::Solution;
 use std::collections::HashSet;
 use glam::IVec2;
-use miette::Result;
+use miette::{miette, Result};
 use itertools::Itertools;
 use rayon::prelude::*;
 
@@ -56,11 +70,24 @@ fn calc_corners(coord: &IVec2, group: &[IVec2], map_height: i32, map_width: i32)
 }
 
 impl Solution<u32> for Day12 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u32> {
+    type Parsed = AoCMap;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
         let map = input.lines()
         .map(|line| line.chars().collect::<Vec<char>>()).collect::<AoCMap>();
 
+        if map.is_empty() {
+            return Err(miette!("The map is empty"));
+        } else if let Some((y, row)) = map.iter().find_position(|row| row.len() != map[0].len()) {
+            return Err(miette!("Line {} is {} wide, the map is {} wide", y + 1, row.len(), map[0].len()));
+        }
+
+        Ok(map)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(map: &Self::Parsed) -> Result<u32> {
         let mut all_visited = HashSet::<IVec2>::new();
         let mut result = 0;
 
@@ -68,7 +95,7 @@ impl Solution<u32> for Day12 {
             let pos = IVec2::new(x as i32, y as i32);
             if !all_visited.contains(&pos) {
                 let mut visited = Vec::<IVec2>::new();
-                let perimeters = get_perimeters(&map, *chr, pos, &mut visited);
+                let perimeters = get_perimeters(map, *chr, pos, &mut visited);
                 let area = visited.len() as u32;
                 result += area * perimeters;
 
@@ -80,11 +107,8 @@ impl Solution<u32> for Day12 {
         Ok(result)
     }
     
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u32> {
-        let map = input.lines()
-        .map(|line| line.chars().collect::<Vec<char>>()).collect::<AoCMap>();
-
+    #[tracing::instrument(skip_all)]
+    fn part2(map: &Self::Parsed) -> Result<u32> {
         let map_height = map.len() as i32;
         let map_width = map[0].len() as i32;
 
@@ -96,7 +120,7 @@ impl Solution<u32> for Day12 {
             let pos = IVec2::new(x as i32, y as i32);
             if !all_visited.contains(&pos) {
                 let mut visited = Vec::<IVec2>::new();
-                let perimeters = get_perimeters(&map, *chr, pos, &mut visited);
+                let perimeters = get_perimeters(map, *chr, pos, &mut visited);
                 let area = visited.len() as u32;
                 result += area * perimeters;
 
@@ -119,6 +143,102 @@ impl Solution<u32> for Day12 {
 mod tests {
     #[allow(unused_imports)]
     use super::{Day12, Solution};
+    #[allow(unused_imports)]
+    use super::property::{check, Shrink};
+    use std::collections::HashSet;
+    use itertools::Itertools;
+
+    /// Plots as letter offsets from 'A', shrunk by dropping outer rows and columns
+    #[derive(Debug, Clone)]
+    struct Garden(Vec<Vec<u8>>);
+
+    impl Garden {
+        #[allow(dead_code)]
+        fn to_input(&self) -> String {
+            self.0.iter().map(|row| row.iter().map(|plot| (b'A' + plot) as char).collect::<String>()).join("\n")
+        }
+    }
+
+    impl Shrink for Garden {
+        fn shrink(&self) -> Vec<Self> {
+            let (height, width) = (self.0.len(), self.0[0].len());
+            let mut smaller = vec![];
+            if height > 1 {
+                smaller.push(Garden(self.0[1..].to_vec()));
+                smaller.push(Garden(self.0[..height - 1].to_vec()));
+            }
+            if width > 1 {
+                smaller.push(Garden(self.0.iter().map(|row| row[1..].to_vec()).collect()));
+                smaller.push(Garden(self.0.iter().map(|row| row[..width - 1].to_vec()).collect()));
+            }
+            (0..height).cartesian_product(0..width).for_each(|(y, x)| self.0[y][x].shrink().into_iter().for_each(|plot| {
+                let mut simpler = self.clone();
+                simpler.0[y][x] = plot;
+                smaller.push(simpler);
+            }));
+
+            smaller
+        }
+    }
+
+    /// Count sides as fence segments whose neighbour to the left along the fence has no matching segment
+    #[allow(dead_code)]
+    fn part2_by_fences(garden: &[Vec<u8>]) -> u32 {
+        let plot = |x: i32, y: i32| garden.get(y as usize).and_then(|row| row.get(x as usize)).copied();
+        let mut seen = HashSet::new();
+        let mut price = 0;
+        for (y, x) in (0..garden.len() as i32).cartesian_product(0..garden[0].len() as i32) {
+            if seen.contains(&(x, y)) {
+                continue;
+            }
+            let kind = plot(x, y);
+            let mut region = HashSet::from([(x, y)]);
+            let mut stack = vec![(x, y)];
+            while let Some((x, y)) = stack.pop() {
+                for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+                    if plot(x + dx, y + dy) == kind && region.insert((x + dx, y + dy)) {
+                        stack.push((x + dx, y + dy));
+                    }
+                }
+            }
+
+            let fenced = |(x, y): (i32, i32), (dx, dy): (i32, i32)| region.contains(&(x, y)) && !region.contains(&(x + dx, y + dy));
+            let sides = region.iter().flat_map(|&(x, y)| [(1, 0), (0, 1), (-1, 0), (0, -1)].map(|dir| ((x, y), dir)))
+                .filter(|&(cell, dir)| fenced(cell, dir) && !fenced((cell.0 + dir.1, cell.1 - dir.0), dir))
+                .count() as u32;
+            price += sides * region.len() as u32;
+            seen.extend(region);
+        }
+
+        price
+    }
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"AAAA
+BBCD
+BBCC
+EEEC"#], super::fuzz::solve::<Day12, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day12::parse("").is_err());
+        assert!(Day12::parse("AA\nB").is_err());
+    }
+
+    #[test]
+    fn test_part2_matches_fences() {
+        let example = ["AAAA", "BBCD", "BBCC", "EEEC"].map(|row| row.bytes().map(|plot| plot - b'A').collect::<Vec<_>>());
+        assert_eq!(part2_by_fences(&example), 80);
+        check(
+            |rng| {
+                let (width, height) = (rng.range(1..=6), rng.range(1..=6));
+                Garden((0..height).map(|_| (0..width).map(|_| rng.below(3) as u8).collect()).collect())
+            },
+            |garden| Day12::part2(&Day12::parse(&garden.to_input()).unwrap()).unwrap() == part2_by_fences(&garden.0),
+        );
+    }
 
     #[test]
     fn test_part1_small() {
@@ -126,7 +246,7 @@ mod tests {
 BBCD
 BBCC
 EEEC"#;
-        let result = Day12::part1(test);
+        let result = Day12::part1(&Day12::parse(test).unwrap());
         assert_eq!(result.unwrap(), 140)
     }
 
@@ -142,7 +262,7 @@ VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
 MMMISSJEEE"#;
-        let result = Day12::part1(test);
+        let result = Day12::part1(&Day12::parse(test).unwrap());
         assert_eq!(result.unwrap(), 1930)
     }
 
@@ -152,7 +272,7 @@ MMMISSJEEE"#;
 BBCD
 BBCC
 EEEC"#;
-        let result = Day12::part2(test);
+        let result = Day12::part2(&Day12::parse(test).unwrap());
         assert_eq!(result.unwrap(), 80)
     }
 
@@ -163,7 +283,7 @@ EXXXX
 EEEEE
 EXXXX
 EEEEE"#;
-        let result = Day12::part2(test);
+        let result = Day12::part2(&Day12::parse(test).unwrap());
         assert_eq!(result.unwrap(), 236)
     }
 
@@ -175,7 +295,7 @@ AAABBA
 ABBAAA
 ABBAAA
 AAAAAA"#;
-        let result = Day12::part2(test);
+        let result = Day12::part2(&Day12::parse(test).unwrap());
         assert_eq!(result.unwrap(), 368)
     }
 }
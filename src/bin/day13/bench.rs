@@ -1,30 +1,26 @@
 mod solution;
-use solution::parse;
 use solution::Day13;
 use solution::Solution;
-use miette::Result;
+
+const INPUT: &str = include_str!("../../../inputs/day13.txt");
 
 fn main() {
     divan::main();
 }
 
 #[divan::bench]
-fn bench_parse() {
-   let _ = parse(divan::black_box(include_str!(
-        "../../../inputs/day13.txt",
-    )));
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day13::parse(divan::black_box(INPUT)));
 }
 
 #[divan::bench]
-fn bench_part1() -> Result<u64> {
-   Day13::part1(divan::black_box(include_str!(
-        "../../../inputs/day13.txt",
-    )))
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day13::parse(INPUT).unwrap();
+    bencher.bench(|| Day13::part1(divan::black_box(&parsed)));
 }
 
 #[divan::bench]
-fn bench_part2() -> Result<u64> {
-    Day13::part2(divan::black_box(include_str!(
-        "../../../inputs/day13.txt",
-    )))
-}
\ No newline at end of file
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day13::parse(INPUT).unwrap();
+    bencher.bench(|| Day13::part2(divan::black_box(&parsed)));
+}
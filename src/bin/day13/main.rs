@@ -1,9 +1,19 @@
 mod solution;
+#[path = "../../telemetry.rs"]
+mod telemetry;
 use solution::Day13;
 use solution::Solution;
+use solution::budget::{self, Budget};
+use miette::Result;
 
-fn main() {
+fn main() -> Result<()> {
+    let _telemetry = telemetry::init();
+    budget::cancel_on_interrupt();
+    let budget = Budget::from_env()?;
     let input = include_str!("../../../inputs/day13.txt");
-    println!("Part 1: {:?}", Day13::part1(input));
-    println!("Part 2: {:?}", Day13::part2(input));
+    let parsed = Day13::parse(input)?;
+    println!("Part 1: {:?}", budget.run(|| Day13::part1(&parsed)));
+    println!("Part 2: {:?}", budget.run(|| Day13::part2(&parsed)));
+
+    Ok(())
 }
@@ -1,10 +1,24 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../property.rs"]
+mod property;
 
 use glam::{DMat2, U64Vec2, UVec2};
 use nom::{bytes::complete::tag, character::complete::{self, newline}, multi::separated_list1, sequence::{pair, preceded, terminated, tuple}, IResult};
 pub use utils::Solution;
-use miette::Result;
+use miette::{miette, Result};
 pub struct Day13;
 
 const A_COST: u64 = 3;
@@ -22,23 +36,23 @@ pub struct Game {
 fn parse_game(input: &str) -> IResult<&str, Game> {
     let (input, tuple) = tuple((
         terminated(pair(
-            preceded(tag("Button A: X+"), complete::u64),
-     preceded(tag(", Y+"), complete::u64)
+            preceded(tag("Button A: X+"), complete::u32),
+     preceded(tag(", Y+"), complete::u32)
         ), newline),
         terminated(pair(
-            preceded(tag("Button B: X+"), complete::u64),
-     preceded(tag(", Y+"), complete::u64)
+            preceded(tag("Button B: X+"), complete::u32),
+     preceded(tag(", Y+"), complete::u32)
         ), newline),
         pair(
-            preceded(tag("Prize: X="), complete::u64),
-     preceded(tag(", Y="), complete::u64)
+            preceded(tag("Prize: X="), complete::u32),
+     preceded(tag(", Y="), complete::u32)
         ),
     ))(input)?;
 
     let game = Game {
-        button_a: UVec2::new(tuple.0.0 as u32, tuple.0.1 as u32),
-        button_b: UVec2::new(tuple.1.0 as u32, tuple.1.1 as u32),
-        prize: UVec2::new(tuple.2.0 as u32, tuple.2.1 as u32),
+        button_a: UVec2::new(tuple.0.0, tuple.0.1),
+        button_b: UVec2::new(tuple.1.0, tuple.1.1),
+        prize: UVec2::new(tuple.2.0, tuple.2.1),
     };
 
     Ok((input, game))
@@ -49,23 +63,33 @@ pub fn parse(input: &str) -> IResult<&str, Vec<Game>> {
 } 
 
 impl Solution<u64> for Day13 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u64> {
-        let games = parse(input).unwrap().1;
-
-        Ok(games.into_iter().map(|game| -> u64 {
-            let button_a = game.button_a;
-            let button_b = game.button_b;
-            let mut current = UVec2::ZERO;
+    type Parsed = Vec<Game>;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let (_, games) = parse(input).map_err(|e| miette!("Failed to parse games: {e}"))?;
+
+        Ok(games)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(games: &Self::Parsed) -> Result<u64> {
+
+        Ok(games.iter().map(|game| -> u64 {
+            // Widened so 100 presses can't overflow
+            let button_a = game.button_a.as_u64vec2();
+            let button_b = game.button_b.as_u64vec2();
+            let prize = game.prize.as_u64vec2();
+            let mut current = U64Vec2::ZERO;
             let mut solutions = vec![];
 
             for i in 0..=100u64 {
-                if current.x > game.prize.x || current.y > game.prize.y {
+                if current.x > prize.x || current.y > prize.y {
                     break;
                 }
-                let distance = game.prize - current;
-                if distance.x % button_a.x == 0 && distance.y % button_a.y == 0 && distance.y / button_a.y == distance.x / button_a.x {
-                    solutions.push(i * B_COST + (distance.x / button_a.x) as u64 * A_COST);
+                let distance = prize - current;
+                if button_a.x != 0 && button_a.y != 0 && distance.x.is_multiple_of(button_a.x) && distance.y.is_multiple_of(button_a.y) && distance.y / button_a.y == distance.x / button_a.x {
+                    solutions.push(i * B_COST + distance.x / button_a.x * A_COST);
                 }
 
                 current += button_b;
@@ -79,50 +103,55 @@ impl Solution<u64> for Day13 {
         }).sum())
     }
     
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u64> {
-        let games = parse(input).unwrap().1;
-        Ok(games.into_iter().map(|game| {
-            let prize = U64Vec2::new(game.prize.x as u64 + PART_2_BONUS, game.prize.y as u64 + PART_2_BONUS);
-
-            let matrix = DMat2::from_cols_array(&[
-                game.button_a.x as f64,
-                game.button_a.y as f64,
-                game.button_b.x as f64,
-                game.button_b.y as f64
-            ]);
-            let det = matrix.determinant();
-
-            if det as i64 == 0 {
-                return 0;
-            }
+    #[tracing::instrument(skip_all)]
+    fn part2(games: &Self::Parsed) -> Result<u64> {
+        Ok(games.iter()
+            .filter_map(|game| cramer(game, PART_2_BONUS))
+            .map(|(a, b)| a * A_COST + b * B_COST)
+            .sum())
+    }
+}
 
-            let mat_x = DMat2::from_cols_array(&[
-                prize.x as f64,
-                prize.y as f64,
-                game.button_b.x as f64,
-                game.button_b.y as f64
-            ]);
-            let det_x = mat_x.determinant();
-            
-            let mat_y = DMat2::from_cols_array(&[
-                game.button_a.x as f64,
-                game.button_a.y as f64,
-                prize.x as f64,
-                prize.y as f64,
-            ]);
-            let det_y = mat_y.determinant();
-                
-            let y = det_y / det;
-            let x = det_x / det;
-
-            if x.trunc() != x || y.trunc() != y || x < 0f64 || y < 0f64 {
-                return 0;
-            }
-            
-            x as u64 * A_COST + y as u64 * B_COST
-        }).sum())
+/// Presses of A and B reaching the prize moved by `bonus`, if the buttons aren't collinear and the solution is whole
+fn cramer(game: &Game, bonus: u64) -> Option<(u64, u64)> {
+    let prize = U64Vec2::new(game.prize.x as u64 + bonus, game.prize.y as u64 + bonus);
+
+    let matrix = DMat2::from_cols_array(&[
+        game.button_a.x as f64,
+        game.button_a.y as f64,
+        game.button_b.x as f64,
+        game.button_b.y as f64
+    ]);
+    let det = matrix.determinant();
+
+    if det as i64 == 0 {
+        return None;
     }
+
+    let mat_x = DMat2::from_cols_array(&[
+        prize.x as f64,
+        prize.y as f64,
+        game.button_b.x as f64,
+        game.button_b.y as f64
+    ]);
+    let det_x = mat_x.determinant();
+
+    let mat_y = DMat2::from_cols_array(&[
+        game.button_a.x as f64,
+        game.button_a.y as f64,
+        prize.x as f64,
+        prize.y as f64,
+    ]);
+    let det_y = mat_y.determinant();
+
+    let y = det_y / det;
+    let x = det_x / det;
+
+    if x.trunc() != x || y.trunc() != y || x < 0f64 || y < 0f64 {
+        return None;
+    }
+
+    Some((x as u64, y as u64))
 }
 
 #[cfg(test)]
@@ -130,7 +159,64 @@ mod tests {
     #[allow(unused_imports)]
     use glam::UVec2;
     #[allow(unused_imports)]
-    use super::{Day13, Solution, Game, parse};
+    use super::{Day13, Solution, Game, parse, cramer, A_COST, B_COST};
+    #[allow(unused_imports)]
+    use super::property::check;
+
+    /// Cheapest way to the prize trying every number of presses up to 100
+    #[allow(dead_code)]
+    fn cheapest_by_presses(game: &Game) -> Option<u64> {
+        (0..=100u32).flat_map(|a| (0..=100u32).map(move |b| (a, b)))
+            .filter(|(a, b)| game.button_a * a + game.button_b * b == game.prize)
+            .map(|(a, b)| a as u64 * A_COST + b as u64 * B_COST)
+            .min()
+    }
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+"#], super::fuzz::solve::<Day13, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        let games = Day13::parse("Button A: X+0, Y+0\nButton B: X+1, Y+1\nPrize: X=5, Y=5").unwrap();
+        assert_eq!(Day13::part1(&games).unwrap(), 0);
+        let games = Day13::parse("Button A: X+1, Y+1\nButton B: X+4000000000, Y+1\nPrize: X=4294967295, Y=5").unwrap();
+        assert_eq!(Day13::part1(&games).unwrap(), 0);
+        assert!(Day13::parse("Button A: X+99999999999, Y+1\nButton B: X+1, Y+1\nPrize: X=5, Y=5").is_err());
+    }
+
+    #[test]
+    fn test_cramer_matches_presses() {
+        check(
+            |rng| {
+                let mut button = || (rng.range(1..=100) as u32, rng.range(1..=100) as u32);
+                let (a, b) = (button(), button());
+                let (presses_a, presses_b) = (rng.range(0..=100) as u32, rng.range(0..=100) as u32);
+                // Most random prizes can't be won, so half of them are reachable by construction
+                let prize = if rng.chance(0.5) {
+                    (a.0 * presses_a + b.0 * presses_b, a.1 * presses_a + b.1 * presses_b)
+                } else {
+                    (rng.range(0..=20_000) as u32, rng.range(0..=20_000) as u32)
+                };
+                (a, b, prize)
+            },
+            |&(a, b, prize)| {
+                let game = Game { button_a: UVec2::from(a), button_b: UVec2::from(b), prize: UVec2::from(prize) };
+                // Collinear buttons have many solutions, Cramer's rule only covers the single one
+                if a.0 * b.1 == a.1 * b.0 {
+                    return true;
+                }
+                let cramer = cramer(&game, 0)
+                    .filter(|(a, b)| *a <= 100 && *b <= 100)
+                    .map(|(a, b)| a * A_COST + b * B_COST);
+                cramer == cheapest_by_presses(&game)
+            },
+        );
+    }
 
     #[test]
     fn test_parse() {
@@ -163,7 +249,7 @@ Prize: X=7870, Y=6450
 Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279"#;
-        let result = Day13::part1(test);
+        let result = Day13::part1(&Day13::parse(test).unwrap());
         assert_eq!(result.unwrap(), 480)
     }
 
@@ -184,7 +270,7 @@ Prize: X=7870, Y=6450
 Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279"#;
-        let result = Day13::part2(test);
+        let result = Day13::part2(&Day13::parse(test).unwrap());
         assert_eq!(result.unwrap(), 875318608908)
     }
 }
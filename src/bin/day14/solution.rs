@@ -1,34 +1,49 @@
 #[path = "../../utils.rs"]
 pub mod utils;
-use std::collections::HashSet;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+use std::{collections::HashSet, fmt::{self, Display}};
 
 use glam::IVec2;
 use nom::{bytes::complete::tag, character::complete::{self, newline, space1}, multi::separated_list1, sequence::{preceded, separated_pair}, IResult};
 pub use utils::Solution;
-use miette::Result;
+use miette::{miette, Result};
 use itertools::Itertools;
 
 pub struct Day14;
 
 const PART1_TIME: i32 = 100;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Robot {
     location: IVec2,
     velocity: IVec2,
 }
 
-fn draw_map(robots: &[Robot], map_size: &IVec2) {
-    let coords = robots.iter().map(|robot| robot.location).collect::<HashSet<_>>();
-    for y in 0..map_size.y {
-        for x in 0..map_size.x {
-            if coords.contains(&IVec2::new(x, y)) {
-                print!("x");
-            } else {
-                print!(".");
+struct RobotsView<'a> {
+    robots: &'a [Robot],
+    map_size: IVec2,
+}
+
+impl Display for RobotsView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let coords = self.robots.iter().map(|robot| robot.location).collect::<HashSet<_>>();
+        for y in 0..self.map_size.y {
+            for x in 0..self.map_size.x {
+                write!(f, "{}", if coords.contains(&IVec2::new(x, y)) { 'x' } else { '.' })?;
             }
+            writeln!(f)?;
         }
-        println!();
+
+        Ok(())
     }
 }
 
@@ -68,15 +83,25 @@ pub fn parse(input: &str) -> IResult<&str, Vec<Robot>> {
 }
 
 impl Solution<u32> for Day14 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u32> {
-        let mut robots = parse(input).unwrap().1;
+    type Parsed = Vec<Robot>;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let (_, robots) = parse(input).map_err(|e| miette!("Failed to parse robots: {e}"))?;
+
+        Ok(robots)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(robots: &Self::Parsed) -> Result<u32> {
+        let mut robots = robots.clone();
         let map_size = if cfg!(test) { IVec2::new(11, 7) } else { IVec2::new(101, 103) };
         let mul_vec = IVec2::splat(PART1_TIME);
 
         robots.iter_mut().for_each(|robot| {
-            let mut location = robot.location;
-            let movement = robot.velocity * mul_vec;
+            // Wrapping first keeps huge velocities from overflowing
+            let mut location = robot.location.rem_euclid(map_size);
+            let movement = robot.velocity.rem_euclid(map_size) * mul_vec;
             location += movement;
             robot.location = location.rem_euclid(map_size);
         });
@@ -84,32 +109,34 @@ impl Solution<u32> for Day14 {
         Ok(get_safety_factor(&robots, &map_size))
     }
     
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u32> {
-        let mut robots = parse(input).unwrap().1;
+    #[tracing::instrument(skip_all)]
+    fn part2(robots: &Self::Parsed) -> Result<u32> {
+        let mut robots = robots.clone();
         let map_size = IVec2::new(101, 103);
-        let mut counter = 1;
-
-        loop {
+        let budget = budget::current();
+
+        // Every robot is back where it started after width * height seconds
+        let period = (map_size.x * map_size.y) as u32;
+        for counter in 1..=period {
+            budget.step()?;
+            if counter.is_multiple_of(1000) {
+                progress::advance("seconds", counter.into(), Some(period.into()));
+            }
             robots.iter_mut().for_each(|robot| {
-                let mut location = robot.location;
-                let movement = robot.velocity;
+                let mut location = robot.location.rem_euclid(map_size);
+                let movement = robot.velocity.rem_euclid(map_size);
                 location += movement;
                 robot.location = location.rem_euclid(map_size);
             });
 
             // Let's assume, that when robots are doing the easter egg, no robots are on top of each other
             if robots.iter().map(|r| r.location).all_unique() {
-                if !cfg!(test) {
-                    draw_map(&robots, &map_size);
-                }
-                break;
+                progress::picture("easter egg", RobotsView { robots: &robots, map_size });
+                return Ok(counter);
             }
-
-            counter += 1;
         }
 
-        Ok(counter)
+        Err(miette!("The robots never stop overlapping"))
     }
 }
 
@@ -118,6 +145,29 @@ mod tests {
     #[allow(unused_imports)]
     use super::{Day14, Solution};
 
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3"#], super::fuzz::solve::<Day14, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        let robots = Day14::parse("p=6,3 v=1,57274712").unwrap();
+        assert!(Day14::part1(&robots).is_ok());
+        assert!(Day14::part2(&Day14::parse("p=1,1 v=1,1\np=1,1 v=1,1").unwrap()).is_err());
+    }
+
     #[test]
     fn test_part1() {
         let test = r#"p=0,4 v=3,-3
@@ -132,7 +182,7 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3"#;
-        let result = Day14::part1(test);
+        let result = Day14::part1(&Day14::parse(test).unwrap());
         assert_eq!(result.unwrap(), 12)
     }
 }
@@ -1,41 +1,26 @@
 mod solution;
-use glam::IVec2;
-use solution::parse_instructions;
-use solution::parse_map;
-use solution::AoCMap;
 use solution::Day15;
-use solution::Instruction;
 use solution::Solution;
-use miette::Result;
+
+const INPUT: &str = include_str!("../../../inputs/day15.txt");
 
 fn main() {
     divan::main();
 }
 
 #[divan::bench]
-fn bench_part1_parse_map() -> (IVec2, AoCMap) {
-   parse_map(divan::black_box(include_str!(
-        "../../../inputs/day15.txt",
-    )).split("\n\n").next().unwrap())
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day15::parse(divan::black_box(INPUT)));
 }
 
 #[divan::bench]
-fn bench_part1_parse_instructions() -> Vec<Instruction> {
-   parse_instructions(divan::black_box(include_str!(
-        "../../../inputs/day15.txt",
-    )).split("\n\n").nth(1).unwrap())
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day15::parse(INPUT).unwrap();
+    bencher.bench(|| Day15::part1(divan::black_box(&parsed)));
 }
 
 #[divan::bench]
-fn bench_part1() -> Result<u32> {
-   Day15::part1(divan::black_box(include_str!(
-        "../../../inputs/day15.txt",
-    )))
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day15::parse(INPUT).unwrap();
+    bencher.bench(|| Day15::part2(divan::black_box(&parsed)));
 }
-
-#[divan::bench]
-fn bench_part2() -> Result<u32> {
-    Day15::part2(divan::black_box(include_str!(
-        "../../../inputs/day15.txt",
-    )))
-}
\ No newline at end of file
@@ -1,15 +1,26 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "warehouse.rs"]
+pub mod warehouse;
 pub use utils::Solution;
-use core::panic;
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 use glam::IVec2;
-use miette::Result;
-use itertools::Itertools;
+use miette::{miette, Result};
+use warehouse::Warehouse;
 
 pub struct Day15;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Obstacle {
     Wall,
     Box,
@@ -17,7 +28,28 @@ pub enum Obstacle {
     BoxRight,
 }
 
-#[derive(Debug)]
+impl Obstacle {
+    fn from_char(chr: char) -> Option<Self> {
+        match chr {
+            '#' => Some(Obstacle::Wall),
+            'O' => Some(Obstacle::Box),
+            '[' => Some(Obstacle::BoxLeft),
+            ']' => Some(Obstacle::BoxRight),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Obstacle::Wall => '#',
+            Obstacle::Box => 'O',
+            Obstacle::BoxLeft => '[',
+            Obstacle::BoxRight => ']',
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Instruction {
     North,
     East,
@@ -26,7 +58,7 @@ pub enum Instruction {
 }
 
 impl Instruction {
-    fn to_delta(&self) -> IVec2 {
+    fn to_delta(self) -> IVec2 {
         match self {
             Instruction::North => IVec2::NEG_Y,
             Instruction::East => IVec2::X,
@@ -34,56 +66,97 @@ impl Instruction {
             Instruction::West => IVec2::NEG_X,
         }
     }
-
-    fn is_vertical(&self) -> bool {
-        matches!(self, Instruction::North | Instruction::South)
-    }
 }
 
 pub type AoCMap = HashMap<IVec2, Obstacle>;
 
-pub fn parse_map(input: &str) -> (IVec2, AoCMap) {
-    let mut start = IVec2::ZERO;
-    let mut map = AoCMap::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, chr)| {
-            let current = IVec2::new(x as i32, y as i32);
-            match chr {
-                '#' => { map.insert(current, Obstacle::Wall); },
-                'O' => { map.insert(current, Obstacle::Box); },
-                '@' => { start = current; },
-                _ => { }
-            }
-        });
-    });
+/// A map with the size of the text it was read from, so it can be written back the same
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Map {
+    pub robot: IVec2,
+    pub size: IVec2,
+    pub cells: AoCMap,
+}
 
-    (start, map)
+impl Map {
+    pub fn is_wide(&self) -> bool {
+        self.cells.values().any(|o| matches!(o, Obstacle::BoxLeft | Obstacle::BoxRight))
+    }
 }
 
-pub fn parse_part2_map(input: &str) -> (IVec2, AoCMap) {
-    let mut start = IVec2::ZERO;
-    let mut map = AoCMap::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, chr)| {
-            let x = x * 2;
+/// Parse either a narrow (`O`) or a wide (`[]`) map
+pub fn parse_map(input: &str) -> Result<Map> {
+    let mut robot = IVec2::ZERO;
+    let mut size = IVec2::ZERO;
+    let mut cells = AoCMap::new();
+    for (y, line) in input.lines().enumerate() {
+        let mut open = None;
+        for (x, chr) in line.chars().enumerate() {
             let current = IVec2::new(x as i32, y as i32);
-            let next = IVec2::new(x as i32 + 1, y as i32);
-            match chr {
-                '#' => {
-                    map.insert(current, Obstacle::Wall);
-                    map.insert(next, Obstacle::Wall);
+            size = size.max(current + IVec2::ONE);
+            let obstacle = Obstacle::from_char(chr);
+            match (open.take(), obstacle) {
+                (None, Some(Obstacle::BoxRight)) => return Err(miette!("Box half ']' at {x},{y} has no '[' before it")),
+                (Some(left), obstacle) if obstacle != Some(Obstacle::BoxRight) => {
+                    return Err(miette!("Box half '[' at {left},{y} has no ']' after it"));
                 },
-                'O' => {
-                    map.insert(current, Obstacle::BoxLeft);
-                    map.insert(next, Obstacle::BoxRight);
-                },
-                '@' => { start = current; },
-                _ => { }
+                (_, Some(Obstacle::BoxLeft)) => open = Some(x),
+                _ => {},
+            }
+            if chr == '@' {
+                robot = current;
+            } else if let Some(obstacle) = obstacle {
+                cells.insert(current, obstacle);
             }
-        });
-    });
+        }
+        if let Some(left) = open {
+            return Err(miette!("Box half '[' at {left},{y} has no ']' after it"));
+        }
+        size.y = y as i32 + 1;
+    }
 
-    (start, map)
+    Ok(Map { robot, size, cells })
+}
+
+/// Double the width of every cell of a narrow map, `O` becomes `[]`
+pub fn widen(map: &Map) -> Result<Map> {
+    let mut cells = AoCMap::new();
+    for (coord, obstacle) in &map.cells {
+        let left = IVec2::new(coord.x * 2, coord.y);
+        let (l, r) = match obstacle {
+            Obstacle::Wall => (Obstacle::Wall, Obstacle::Wall),
+            Obstacle::Box => (Obstacle::BoxLeft, Obstacle::BoxRight),
+            Obstacle::BoxLeft | Obstacle::BoxRight => return Err(miette!("The map is already wide")),
+        };
+        cells.insert(left, l);
+        cells.insert(left + IVec2::X, r);
+    }
+
+    Ok(Map {
+        robot: IVec2::new(map.robot.x * 2, map.robot.y),
+        size: IVec2::new(map.size.x * 2, map.size.y),
+        cells,
+    })
+}
+
+/// Writes the map back in the puzzle notation, a line per row
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let current = IVec2::new(x, y);
+                let chr = match self.cells.get(&current) {
+                    Some(obstacle) => obstacle.to_char(),
+                    None if current == self.robot => '@',
+                    None => '.',
+                };
+                write!(f, "{}", chr)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
 }
 
 pub fn parse_instructions(input: &str) -> Vec<Instruction> {
@@ -96,177 +169,201 @@ pub fn parse_instructions(input: &str) -> Vec<Instruction> {
     }).collect::<Vec<_>>()
 }
 
-fn can_move(map: &AoCMap, start: &IVec2, instruction: &Instruction) -> bool {
-    let delta = instruction.to_delta();
-    let next = start + delta;
-    if let Some(obstacle) = map.get(&next) {
-        match obstacle {
-            Obstacle::Box => can_move(map, &next, instruction),
-            Obstacle::BoxLeft if instruction.is_vertical() => {
-                can_move(map, &next, instruction)
-                && can_move(map, &(next + Instruction::East.to_delta()), instruction)
-            },
-            Obstacle::BoxRight if instruction.is_vertical() => {
-                can_move(map, &next, instruction)
-                && can_move(map, &(next + Instruction::West.to_delta()), instruction)
-            },
-            Obstacle::BoxLeft | Obstacle::BoxRight => can_move(map, &next, instruction),
-            Obstacle::Wall => false,
-        }
-    } else {
-        true
+fn print_map(warehouse: &Warehouse) {
+    if let Some(map) = warehouse.to_map() {
+        progress::picture("warehouse", map);
     }
 }
 
-fn make_move(map: &mut AoCMap, robot: &mut IVec2, instruction: &Instruction) -> bool {
-    if !can_move(map, robot, instruction) {
-        return false;
-    }
-    let delta = instruction.to_delta();
-
-    // Find the next free slot
-    let mut current = *robot;
-    current += delta;
-    let mut first_box: Option<IVec2> = None;
-    while map.get(&current).is_some() {
-        if first_box.is_none() {
-            first_box = Some(current);
-        }
-        current += delta;
-    }
+/// Run every instruction and take the GPS sum they end with
+fn final_gps_sum(map: &Map, instructions: &[Instruction]) -> Result<u32> {
+    let mut warehouse = Warehouse::from_map(map);
+    let trace = warehouse.gps_trace(instructions, &budget::current())?;
+    print_map(&warehouse);
 
-    *robot += delta;
+    trace.last().copied().unwrap_or(warehouse.gps_sum()).ok_or(miette!("A box was pushed off the map"))
+}
 
-    // Move the first box to the first free space
-    if let Some(first_box) = first_box {
-        map.remove(&first_box);
-        map.insert(current, Obstacle::Box);
-    }
+impl Solution<u32> for Day15 {
+    type Parsed = (Map, Vec<Instruction>);
 
-    true
-}
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let (map, instructions) = input.split_once("\n\n").ok_or(miette!("Missing blank line between map and instructions"))?;
+        if !map.contains('@') {
+            return Err(miette!("The map has no robot"));
+        }
 
-fn make_part2_move(map: &mut AoCMap, robot: &mut IVec2, instruction: &Instruction) -> bool {
-    if !can_move(map, robot, instruction) {
-        return false;
+        Ok((parse_map(map)?, parse_instructions(instructions)))
     }
-    let delta = instruction.to_delta();
-
-    // Find the next free slot
-    let mut current = *robot;
-    current += delta;
-    let mut boxes: Vec<Vec<_>> = vec![];
-    'outer: loop {
-        let prev_boxes = if boxes.is_empty() { vec![*robot] } else { boxes.iter().next_back().unwrap().clone() };
-        let mut all_free = true;
-        for b in prev_boxes {
-            match map.get(&(b + delta)) {
-                Some(Obstacle::BoxLeft) if instruction.is_vertical() => {
-                    all_free = false;
-                    boxes.push(vec![b + delta, b + delta + Instruction::East.to_delta()]);
-                },
-                Some(Obstacle::BoxRight) if instruction.is_vertical() => {
-                    all_free = false;
-                    boxes.push(vec![b + delta, b + delta + Instruction::West.to_delta()]);
-                },
-                Some(Obstacle::BoxLeft) | Some(Obstacle::BoxRight) if !instruction.is_vertical() => {
-                    all_free = false;
-                    boxes.push(vec![b + delta]);
-                },
-                None => { }
-                _ => { panic!(); }
-            }
-        }
-        if all_free {
-            break 'outer;
+
+    #[tracing::instrument(skip_all)]
+    fn part1((map, instructions): &Self::Parsed) -> Result<u32> {
+        final_gps_sum(map, instructions)
+    }
+    
+    #[tracing::instrument(skip_all)]
+    fn part2((map, instructions): &Self::Parsed) -> Result<u32> {
+        if map.is_wide() {
+            final_gps_sum(map, instructions)
+        } else {
+            final_gps_sum(&widen(map)?, instructions)
         }
     }
-
-    *robot += delta;
-
-    // Move the first box to the first free space
-    boxes.into_iter().rev().unique().for_each(|b| {
-        b.iter().for_each(|c| {
-            if let Some(old) = map.remove(c) {
-                let new_coord = c + delta;
-                map.insert(new_coord, old);
-            }
-        });
-    });
-
-    // print_map(map, robot, IVec2::new(20, 10));
-
-    true
 }
 
-#[allow(dead_code)]
-fn print_map(map: &HashMap<IVec2, Obstacle>, robot: &IVec2, map_size: IVec2) {
-    (0..map_size.y).for_each(|y| {
-        (0..map_size.x).for_each(|x| {
-            match map.get(&IVec2::new(x, y)) {
-                Some(Obstacle::Box) => print!("O"),
-                Some(Obstacle::BoxLeft) => print!("["),
-                Some(Obstacle::BoxRight) => print!("]"),
-                Some(Obstacle::Wall) => print!("#"),
-                _ if robot.x == x && robot.y == y => print!("@"),
-                _ => print!("."),
-            }
-        });
-        println!();
-    });
-    println!();
-
-    let mut s = String::new();
-    let _ = std::io::stdin().read_line(&mut s);
-}
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Day15, Solution, parse_map, parse_instructions, widen};
+    #[allow(unused_imports)]
+    use super::warehouse::Warehouse;
 
-fn calc_score(map: &AoCMap) -> u32 {
-    map.iter().map(|(c, obj)| {
-        match obj {
-            Obstacle::Box | Obstacle::BoxLeft => (c.y * 100 + c.x) as u32,
-            _ => 0
-        }
-    }).sum()
-}
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########"#], super::fuzz::solve::<Day15, _>);
+    }
 
-impl Solution<u32> for Day15 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u32> {
-        let mut parts = input.split("\n\n");
-        let (mut robot, mut map) = parse_map(parts.next().unwrap());
-        let instructions = parse_instructions(parts.next().unwrap());
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day15::parse("#.O\n\n<").is_err());
+        assert!(Day15::part1(&Day15::parse("OO@\n\n<<<<<").unwrap()).is_err());
+    }
 
-        instructions.into_iter().for_each(|i| {
-            make_move(&mut map, &mut robot, &i);
-        });
+    #[test]
+    fn test_unmatched_box_halves() {
+        assert!(parse_map("#[.@#").is_err());
+        assert!(parse_map("#[@]#").is_err());
+        assert!(parse_map("#.]@#").is_err());
+        assert!(parse_map("#@.[\n#..]").is_err());
+        assert!(Day15::parse("##[\n#@.\n\n<").is_err());
+        assert!(parse_map("#[][]@#").is_ok());
+    }
 
-        let score = calc_score(&map);
+    #[test]
+    fn test_map_round_trip() {
+        let narrow = r#"########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+"#;
+        let map = parse_map(narrow).unwrap();
+        assert_eq!(map.to_string(), narrow);
 
-        Ok(score)
+        let wide = r#"##########
+##..[][]##
+##.[]@O.##
+##########
+"#;
+        let map = parse_map(wide).unwrap();
+        assert_eq!(map.to_string(), wide);
+        assert_eq!(Warehouse::from_map(&map).to_map(), Some(map));
+
+        // Rows and columns of floor at the edges keep their place
+        let open = "....\n.@O.\n....\n";
+        assert_eq!(parse_map(open).unwrap().to_string(), open);
     }
-    
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u32> {
-        let mut parts = input.split("\n\n");
-        let (mut robot, mut map) = parse_part2_map(parts.next().unwrap());
-        let instructions = parse_instructions(parts.next().unwrap());
-
-        instructions.into_iter().for_each(|i| {
-            make_part2_move(&mut map, &mut robot, &i);
-        });
 
-        let score = calc_score(&map);
+    #[test]
+    fn test_widen() {
+        let map = parse_map(r#"#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######"#).unwrap();
+        let wide = widen(&map).unwrap();
+        assert_eq!(wide.to_string(), r#"##############
+##......##..##
+##..........##
+##....[][]@.##
+##....[]....##
+##..........##
+##############
+"#);
+        assert!(widen(&wide).is_err());
+    }
 
-        Ok(score)
-        // 1274891 too low
-        // 1399853 too low
+    #[test]
+    fn test_part1_small_steps() {
+        let map = parse_map(r#"########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########"#).unwrap();
+        let instructions = parse_instructions("<^^>>>vv<v>>v<<");
+        let mut warehouse = Warehouse::from_map(&map);
+
+        warehouse.replay(&instructions, 4);
+        assert_eq!(warehouse.to_map().unwrap().to_string(), r#"########
+#..@OO.#
+##..O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+"#);
+
+        warehouse.replay(&instructions, instructions.len());
+        assert_eq!(warehouse.to_map().unwrap().to_string(), r#"########
+#....OO#
+##.....#
+#.....O#
+#.#O@..#
+#...O..#
+#...O..#
+########
+"#);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    #[allow(unused_imports)]
-    use super::{Day15, Solution, parse_map};
+    #[test]
+    fn test_part2_small_steps() {
+        let map = parse_map(r#"#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######"#).unwrap();
+        let instructions = parse_instructions("<vv<<^^<<^^");
+        let mut warehouse = Warehouse::from_map(&widen(&map).unwrap());
+
+        warehouse.replay(&instructions, 6);
+        assert_eq!(warehouse.to_map().unwrap().to_string(), r#"##############
+##......##..##
+##...[][]...##
+##....[]....##
+##.....@....##
+##..........##
+##############
+"#);
+
+        warehouse.replay(&instructions, instructions.len());
+        assert_eq!(warehouse.to_map().unwrap().to_string(), r#"##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############
+"#);
+    }
 
     #[test]
     fn test_part1_small() {
@@ -280,7 +377,7 @@ mod tests {
 ########
 
 <^^>>>vv<v>>v<<"#;
-        let result = Day15::part1(test);
+        let result = Day15::part1(&Day15::parse(test).unwrap());
         assert_eq!(result.unwrap(), 2028)
     }
 
@@ -307,7 +404,7 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
 ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#;
-        let result = Day15::part1(test);
+        let result = Day15::part1(&Day15::parse(test).unwrap());
         assert_eq!(result.unwrap(), 10092)
     }
 
@@ -322,7 +419,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#;
 #######
 
 <vv<<^^<<^^"#;
-        let result = Day15::part2(test);
+        let result = Day15::part2(&Day15::parse(test).unwrap());
         assert_eq!(result.unwrap(), 618)
     }
 
@@ -349,7 +446,7 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
 ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#;
-        let result = Day15::part2(test);
+        let result = Day15::part2(&Day15::parse(test).unwrap());
         assert_eq!(result.unwrap(), 9021)
     }
 
@@ -0,0 +1,306 @@
+use std::{collections::{HashMap, HashSet}, iter::once};
+use glam::IVec2;
+
+use super::{budget::{Budget, Exhausted}, AoCMap, Instruction, Map, Obstacle};
+
+/// A box of any width, identified by its leftmost cell
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Crate {
+    pub position: IVec2,
+    pub width: i32,
+}
+
+impl Crate {
+    pub fn new(position: IVec2, width: i32) -> Self {
+        Self { position, width }
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..self.width).map(|dx| self.position + IVec2::new(dx, 0))
+    }
+
+    /// None for crates at negative coordinates, which only happens when the map isn't walled in
+    pub fn gps(&self) -> Option<u32> {
+        u32::try_from(self.position.y * 100 + self.position.x).ok()
+    }
+}
+
+/// Undo record for a single instruction
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub instruction: Instruction,
+    pub robot_moved: bool,
+    pub pushed: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Warehouse {
+    robot: IVec2,
+    size: IVec2,
+    walls: HashSet<IVec2>,
+    crates: Vec<Crate>,
+    occupied: HashMap<IVec2, usize>,
+    history: Vec<Step>,
+    /// GPS sum of the crates kept up to date on every push, and how many crates have no GPS coordinate
+    gps_total: i64,
+    off_map: usize,
+}
+
+impl Warehouse {
+    pub fn new(robot: IVec2, walls: HashSet<IVec2>, crates: Vec<Crate>) -> Self {
+        let size = walls.iter().copied()
+            .chain(crates.iter().flat_map(Crate::cells))
+            .chain(once(robot))
+            .fold(IVec2::ZERO, |size, cell| size.max(cell + IVec2::ONE));
+        let occupied = crates.iter().enumerate()
+            .flat_map(|(id, c)| c.cells().map(move |cell| (cell, id)).collect::<Vec<_>>())
+            .collect();
+
+        let mut warehouse = Self {
+            robot,
+            size,
+            walls,
+            crates,
+            occupied,
+            history: vec![],
+            gps_total: 0,
+            off_map: 0,
+        };
+        (0..warehouse.crates.len()).for_each(|id| warehouse.count_gps(id, 1));
+
+        warehouse
+    }
+
+    pub fn from_map(map: &Map) -> Self {
+        let mut walls = HashSet::new();
+        let mut crates = vec![];
+        map.cells.iter().for_each(|(coord, obstacle)| match obstacle {
+            Obstacle::Wall => { walls.insert(*coord); },
+            Obstacle::Box => crates.push(Crate::new(*coord, 1)),
+            Obstacle::BoxLeft => crates.push(Crate::new(*coord, 2)),
+            Obstacle::BoxRight => {},
+        });
+        // Keep crate ids stable between runs regardless of hash order
+        crates.sort_by_key(|c| (c.position.y, c.position.x));
+
+        Self { size: map.size, ..Self::new(map.robot, walls, crates) }
+    }
+
+    /// The map in the puzzle notation, None when a crate is wider than the two cells it can show
+    pub fn to_map(&self) -> Option<Map> {
+        let mut cells = self.walls.iter().map(|w| (*w, Obstacle::Wall)).collect::<AoCMap>();
+        for c in &self.crates {
+            match c.width {
+                1 => { cells.insert(c.position, Obstacle::Box); },
+                2 => {
+                    cells.insert(c.position, Obstacle::BoxLeft);
+                    cells.insert(c.position + IVec2::X, Obstacle::BoxRight);
+                },
+                _ => return None,
+            }
+        }
+
+        Some(Map { robot: self.robot, size: self.size, cells })
+    }
+
+    fn count_gps(&mut self, id: usize, sign: i64) {
+        match self.crates[id].gps() {
+            Some(gps) => self.gps_total += sign * gps as i64,
+            None => self.off_map = self.off_map.wrapping_add_signed(sign as isize),
+        }
+    }
+
+    pub fn gps_sum(&self) -> Option<u32> {
+        if self.off_map > 0 {
+            return None;
+        }
+        u32::try_from(self.gps_total).ok()
+    }
+
+    /// Collect every crate that would be pushed, or None if a wall is in the way
+    fn pushed_crates(&self, delta: IVec2) -> Option<Vec<usize>> {
+        let mut pushed = vec![];
+        let mut seen = HashSet::new();
+        let mut frontier = vec![self.robot + delta];
+
+        while let Some(cell) = frontier.pop() {
+            if self.walls.contains(&cell) {
+                return None;
+            }
+            let Some(&id) = self.occupied.get(&cell) else {
+                continue;
+            };
+            if !seen.insert(id) {
+                continue;
+            }
+            pushed.push(id);
+            self.crates[id].cells()
+                .map(|c| c + delta)
+                .filter(|c| self.occupied.get(c) != Some(&id))
+                .for_each(|c| frontier.push(c));
+        }
+
+        Some(pushed)
+    }
+
+    fn shift(&mut self, ids: &[usize], delta: IVec2) {
+        ids.iter().for_each(|id| {
+            self.count_gps(*id, -1);
+            self.crates[*id].cells().for_each(|cell| { self.occupied.remove(&cell); });
+        });
+        ids.iter().for_each(|id| {
+            self.crates[*id].position += delta;
+            self.crates[*id].cells().for_each(|cell| { self.occupied.insert(cell, *id); });
+            self.count_gps(*id, 1);
+        });
+    }
+
+    /// Apply a single instruction, returns whether the robot moved
+    pub fn step(&mut self, instruction: &Instruction) -> bool {
+        let delta = instruction.to_delta();
+        let step = match self.pushed_crates(delta) {
+            Some(pushed) => {
+                self.shift(&pushed, delta);
+                self.robot += delta;
+                Step { instruction: *instruction, robot_moved: true, pushed }
+            },
+            None => Step { instruction: *instruction, robot_moved: false, pushed: vec![] },
+        };
+        let moved = step.robot_moved;
+        self.history.push(step);
+
+        moved
+    }
+
+    /// Revert the latest instruction
+    pub fn undo(&mut self) -> Option<Instruction> {
+        let step = self.history.pop()?;
+        if step.robot_moved {
+            let delta = -step.instruction.to_delta();
+            self.shift(&step.pushed, delta);
+            self.robot += delta;
+        }
+
+        Some(step.instruction)
+    }
+
+    /// Undo until only the first `steps` instructions remain applied
+    pub fn rewind(&mut self, steps: usize) {
+        while self.history.len() > steps {
+            self.undo();
+        }
+    }
+
+    /// Move to the state after the first `steps` instructions of the stream the history was made from,
+    /// undoing or applying only the instructions in between
+    pub fn replay(&mut self, instructions: &[Instruction], steps: usize) {
+        self.rewind(steps);
+        let applied = self.history.len();
+        instructions.iter().take(steps).skip(applied).for_each(|i| { self.step(i); });
+    }
+
+    /// Apply the instructions after the ones already applied, with the GPS sum after each of them,
+    /// every instruction is a step of the budget
+    pub fn gps_trace(&mut self, instructions: &[Instruction], budget: &Budget) -> Result<Vec<Option<u32>>, Exhausted> {
+        (self.history.len() + 1..=instructions.len()).map(|steps| {
+            budget.step()?;
+            self.replay(instructions, steps);
+            Ok(self.gps_sum())
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use std::collections::HashSet;
+    #[allow(unused_imports)]
+    use glam::IVec2;
+    #[allow(unused_imports)]
+    use super::{Budget, Crate, Warehouse};
+    #[allow(unused_imports)]
+    use super::super::{parse_instructions, parse_map, widen, Instruction};
+
+    #[allow(dead_code)]
+    const SMALL: &str = r#"#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+"#;
+
+    #[allow(dead_code)]
+    fn corridor(crates: Vec<Crate>) -> Warehouse {
+        let walls = (0..10).flat_map(|x| [IVec2::new(x, 0), IVec2::new(x, 6)])
+            .chain((0..7).flat_map(|y| [IVec2::new(0, y), IVec2::new(9, y)]))
+            .collect::<HashSet<_>>();
+        Warehouse::new(IVec2::new(4, 5), walls, crates)
+    }
+
+    #[test]
+    fn test_push_wide_crate() {
+        let mut warehouse = corridor(vec![Crate::new(IVec2::new(2, 4), 4)]);
+        assert!(warehouse.step(&Instruction::North));
+        assert!(warehouse.step(&Instruction::North));
+        assert_eq!(warehouse.crates[0].position, IVec2::new(2, 2));
+        assert!(warehouse.step(&Instruction::North));
+        assert!(!warehouse.step(&Instruction::North));
+        assert_eq!(warehouse.robot, IVec2::new(4, 2));
+    }
+
+    #[test]
+    fn test_push_branching_crates() {
+        let mut warehouse = corridor(vec![
+            Crate::new(IVec2::new(3, 4), 2),
+            Crate::new(IVec2::new(2, 3), 2),
+            Crate::new(IVec2::new(4, 3), 3),
+        ]);
+        assert!(warehouse.step(&Instruction::North));
+        assert!(warehouse.step(&Instruction::North));
+        assert_eq!(warehouse.crates[2].position, IVec2::new(4, 1));
+
+        // Both top crates are now against the wall, so nothing moves
+        assert!(!warehouse.step(&Instruction::North));
+        assert_eq!(warehouse.crates[2].position, IVec2::new(4, 1));
+
+        // A crate three cells wide has no map notation
+        assert_eq!(warehouse.to_map(), None);
+    }
+
+    #[test]
+    fn test_undo_restores_state() {
+        let map = widen(&parse_map(SMALL).unwrap()).unwrap();
+        let instructions = parse_instructions("<vv<<^^<<^^");
+        let mut warehouse = Warehouse::from_map(&map);
+        let initial = (warehouse.robot, warehouse.crates.clone());
+
+        instructions.iter().for_each(|i| { warehouse.step(i); });
+        assert_eq!(warehouse.gps_sum(), Some(618));
+        while warehouse.undo().is_some() {}
+
+        assert_eq!((warehouse.robot, warehouse.crates.clone()), initial);
+    }
+
+    #[test]
+    fn test_replay_moves_both_ways() {
+        let map = widen(&parse_map(SMALL).unwrap()).unwrap();
+        let instructions = parse_instructions("<vv<<^^<<^^");
+        let mut warehouse = Warehouse::from_map(&map);
+        let trace = warehouse.gps_trace(&instructions, &Budget::unlimited()).unwrap();
+        assert_eq!(trace.len(), instructions.len());
+        assert_eq!(trace.last(), Some(&Some(618)));
+
+        // Back and forth from wherever the last replay left the warehouse
+        [0, 7, 3, 11, 5, 0, 11].into_iter().for_each(|steps| {
+            warehouse.replay(&instructions, steps);
+            assert_eq!(warehouse.history.len(), steps);
+            let recounted = warehouse.crates.iter().map(|c| c.gps().unwrap()).sum::<u32>();
+            assert_eq!(warehouse.gps_sum(), Some(recounted));
+            if steps > 0 {
+                assert_eq!(warehouse.gps_sum(), trace[steps - 1]);
+            }
+        });
+    }
+}
@@ -1,12 +1,22 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
 pub use utils::Solution;
 
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt::{self, Display}};
 use glam::IVec2;
 use itertools::Itertools;
 use pathfinding::prelude::{astar, astar_bag_collect};
-use miette::Result;
+use miette::{miette, Result};
 
 pub struct Day16;
 
@@ -39,7 +49,7 @@ fn find_successor(map: &AoCMap, coord: &IVec2, current_direction: &Direction) ->
     DIRECTIONS.iter().filter_map(|dir| {
         let delta = dir.to_delta();
         let next = coord + delta;
-        if map[next.y as usize][next.x  as usize] == '.' {
+        if map.get(next.y as usize).and_then(|row| row.get(next.x as usize)) == Some(&'.') {
             return Some(if current_direction == dir {
                 ((dir.clone(), next), STEP_SCORE)
             } else {
@@ -50,7 +60,7 @@ fn find_successor(map: &AoCMap, coord: &IVec2, current_direction: &Direction) ->
     }).collect_vec()
 }
 
-pub fn solve_part1(map: &AoCMap, start: &IVec2, end: &IVec2) -> u32 {
+pub fn solve_part1(map: &AoCMap, start: &IVec2, end: &IVec2) -> Result<u32> {
     let path = astar(
         &(Direction::East, *start),
         |(dir, coord)| {
@@ -60,16 +70,14 @@ pub fn solve_part1(map: &AoCMap, start: &IVec2, end: &IVec2) -> u32 {
         |(_, coord)| coord == end
     );
 
-    dbg!(&path);
-
     if let Some((_, score)) = path {
-        score
+        Ok(score)
     } else {
-        panic!();
+        Err(miette!("There is no path from the start to the end"))
     }
 }
 
-pub fn solve_part2(map: &AoCMap, start: &IVec2, end: &IVec2) -> u32 {
+pub fn solve_part2(map: &AoCMap, start: &IVec2, end: &IVec2) -> Result<u32> {
     let paths = astar_bag_collect(
         &(Direction::East, *start),
         |(dir, coord)| {
@@ -80,45 +88,49 @@ pub fn solve_part2(map: &AoCMap, start: &IVec2, end: &IVec2) -> u32 {
     );
 
     if let Some((paths, _)) = paths {
-        println!("Found {} paths", paths.len());
+        progress::note(format_args!("Found {} paths", paths.len()));
         let points = paths.into_iter().flat_map(|path| {
             path.iter().map(|p| p.1).collect_vec()
         }).collect::<HashSet<IVec2>>();
 
-        // print_map_paths(map, &points);
+        progress::picture("best seats", PathsView { map, points: &points });
 
-        points.len() as u32
+        Ok(points.len() as u32)
     } else {
-        panic!();
+        Err(miette!("There is no path from the start to the end"))
     }
 }
 
-#[allow(dead_code)]
-fn print_map_paths(map: &[Vec<char>], points: &HashSet<IVec2>) {
-    map.iter().enumerate().for_each(|(y, line)| {
-        line.iter().enumerate().for_each(|(x, chr)| {
-            if points.contains(&IVec2::new(x as i32, y as i32)) {
-                print!("O");
-            } else {
-                print!("{}", chr);
+/// The map with every tile on one of the best paths drawn as `O`
+struct PathsView<'a> {
+    map: &'a [Vec<char>],
+    points: &'a HashSet<IVec2>,
+}
+
+impl Display for PathsView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, line) in self.map.iter().enumerate() {
+            for (x, chr) in line.iter().enumerate() {
+                write!(f, "{}", if self.points.contains(&IVec2::new(x as i32, y as i32)) { 'O' } else { *chr })?;
             }
-        });
-        println!();
-    });
-    println!();
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
 }
 
-fn parse_map(input: &str) -> (AoCMap, IVec2, IVec2) {
-    let (mut start, mut end) = (IVec2::ZERO, IVec2::ZERO);
+fn parse_map(input: &str) -> Result<(AoCMap, IVec2, IVec2)> {
+    let (mut start, mut end) = (None, None);
     let map = input.lines().enumerate().map(|(y, line)| {
         line.chars().enumerate().map(|(x, chr)| {
             match chr {
                 'S' => {
-                    start = IVec2::new(x as i32, y as i32);
+                    start = Some(IVec2::new(x as i32, y as i32));
                     '.'
                 }
                 'E' => {
-                    end = IVec2::new(x as i32, y as i32);
+                    end = Some(IVec2::new(x as i32, y as i32));
                     '.'
                 }
                 _ => chr,
@@ -127,24 +139,25 @@ fn parse_map(input: &str) -> (AoCMap, IVec2, IVec2) {
     })
     .collect::<AoCMap>();
 
-    (map, start, end)
+    Ok((map, start.ok_or(miette!("The map has no start"))?, end.ok_or(miette!("The map has no end"))?))
 }
 
 impl Solution<u32> for Day16 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u32> {
-        let (map, start, end) = parse_map(input);
+    type Parsed = (AoCMap, IVec2, IVec2);
 
-        let score = solve_part1(&map, &start, &end);
-        Ok(score)
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_map(input)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1((map, start, end): &Self::Parsed) -> Result<u32> {
+        solve_part1(map, start, end)
     }
     
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u32> {
-        let (map, start, end) = parse_map(input);
-    
-        let score = solve_part2(&map, &start, &end);
-        Ok(score)
+    #[tracing::instrument(skip_all)]
+    fn part2((map, start, end): &Self::Parsed) -> Result<u32> {
+        solve_part2(map, start, end)
     }
 }
 
@@ -156,6 +169,32 @@ mod tests {
     #[allow(unused_imports)]
     use super::{Day16, Solution, parse_map};
 
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############"#], super::fuzz::solve::<Day16, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day16::parse("").is_err());
+        assert!(Day16::part1(&Day16::parse("SE").unwrap()).is_ok());
+        assert!(Day16::part2(&Day16::parse("S#E").unwrap()).is_err());
+    }
+
     #[test]
     fn test_parse_map() {
         let test = r#"###############
@@ -173,7 +212,7 @@ mod tests {
 #.###.#.#.#.#.#
 #S..#.....#...#
 ###############"#;
-        let result = parse_map(test);
+        let result = parse_map(test).unwrap();
         assert_eq!(result.1, IVec2::new(1, 13));
         assert_eq!(result.2, IVec2::new(13, 1));
     }
@@ -195,7 +234,7 @@ mod tests {
 #.###.#.#.#.#.#
 #S..#.....#...#
 ###############"#;
-        let result = Day16::part1(test);
+        let result = Day16::part1(&Day16::parse(test).unwrap());
         assert_eq!(result.unwrap(), 7036)
     }
 
@@ -218,7 +257,7 @@ mod tests {
 #.#.#.#########.#
 #S#.............#
 #################"#;
-        let result = Day16::part1(test);
+        let result = Day16::part1(&Day16::parse(test).unwrap());
         assert_eq!(result.unwrap(), 11048)
     }
 
@@ -239,7 +278,7 @@ mod tests {
 #.###.#.#.#.#.#
 #S..#.....#...#
 ###############"#;
-        let result = Day16::part2(test);
+        let result = Day16::part2(&Day16::parse(test).unwrap());
         assert_eq!(result.unwrap(), 45)
     }
 
@@ -262,7 +301,7 @@ mod tests {
 #.#.#.#########.#
 #S#.............#
 #################"#;
-        let result = Day16::part2(test);
+        let result = Day16::part2(&Day16::parse(test).unwrap());
         assert_eq!(result.unwrap(), 64)
     }
 }
@@ -1,22 +1,64 @@
 mod solution;
-use solution::Day15;
-use solution::Solution;
-use miette::Result;
+use solution::Day17;
+use solution::{Overflow, Solution};
+use solution::budget::Budget;
+use solution::compiled::Compiled;
+
+const INPUT: &str = include_str!("../../../inputs/day17.txt");
 
 fn main() {
     divan::main();
 }
 
 #[divan::bench]
-fn bench_part1() -> Result<String> {
-   Day15::part1(divan::black_box(include_str!(
-        "../../../inputs/day15.txt",
-    )))
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day17::parse(divan::black_box(INPUT)));
+}
+
+#[divan::bench]
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day17::parse(INPUT).unwrap();
+    bencher.bench(|| Day17::part1(divan::black_box(&parsed)));
 }
 
 #[divan::bench]
-fn bench_part2() -> Result<String> {
-    Day15::part2(divan::black_box(include_str!(
-        "../../../inputs/day15.txt",
-    )))
-}
\ No newline at end of file
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day17::parse(INPUT).unwrap();
+    bencher.bench(|| Day17::part2(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part1_strict(bencher: divan::Bencher) {
+    let parsed = Day17::parse(INPUT).unwrap().with_overflow(Overflow::Strict);
+    bencher.bench(|| Day17::part1(divan::black_box(&parsed)));
+}
+
+/// Values of register A each search bench runs the input program with
+const SEARCH: u64 = 1024;
+
+#[divan::bench]
+fn bench_search_interpreted(bencher: divan::Bencher) {
+    let parsed = Day17::parse(INPUT).unwrap().narrow().cloned().unwrap();
+    let budget = Budget::unlimited();
+    bencher.bench(|| {
+        for a in 0..SEARCH {
+            let mut computer = parsed.clone().with_register_a(a);
+            computer.run(None, &budget).unwrap();
+            divan::black_box(computer);
+        }
+    });
+}
+
+#[divan::bench]
+fn bench_search_compiled(bencher: divan::Bencher) {
+    let parsed = Day17::parse(INPUT).unwrap().narrow().cloned().unwrap();
+    let budget = Budget::unlimited();
+    bencher.bench(|| {
+        let compiled = Compiled::new(&parsed);
+        for a in 0..SEARCH {
+            let mut computer = parsed.clone().with_register_a(a);
+            compiled.run(&mut computer, None, &budget).unwrap();
+            divan::black_box(computer);
+        }
+    });
+}
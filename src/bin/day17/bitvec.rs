@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use super::sat::{Lit, Solver};
+
+/// A single bit of a formula, constants are folded away before they reach the solver
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bit {
+    Const(bool),
+    Lit(Lit),
+}
+
+impl Bit {
+    pub fn not(self) -> Self {
+        match self {
+            Bit::Const(value) => Bit::Const(!value),
+            Bit::Lit(lit) => Bit::Lit(!lit),
+        }
+    }
+}
+
+/// Bits from least to most significant
+pub type BitVec = Vec<Bit>;
+
+/// Gates over the solver's variables, each shared by every formula that uses it
+#[derive(Debug)]
+pub struct Circuit {
+    pub solver: Solver,
+    ands: HashMap<(Lit, Lit), Lit>,
+    xors: HashMap<(Lit, Lit), Lit>,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Circuit {
+    pub fn new() -> Self {
+        Self { solver: Solver::new(), ands: HashMap::new(), xors: HashMap::new() }
+    }
+
+    /// An unknown bit
+    pub fn input(&mut self) -> Bit {
+        Bit::Lit(Lit::new(self.solver.new_var(), false))
+    }
+
+    pub fn and(&mut self, left: Bit, right: Bit) -> Bit {
+        let (left, right) = match (left, right) {
+            (Bit::Const(false), _) | (_, Bit::Const(false)) => return Bit::Const(false),
+            (Bit::Const(true), other) | (other, Bit::Const(true)) => return other,
+            (Bit::Lit(left), Bit::Lit(right)) if left == right => return Bit::Lit(left),
+            (Bit::Lit(left), Bit::Lit(right)) if left == !right => return Bit::Const(false),
+            (Bit::Lit(left), Bit::Lit(right)) => (left.min(right), left.max(right)),
+        };
+        if let Some(gate) = self.ands.get(&(left, right)) {
+            return Bit::Lit(*gate);
+        }
+
+        let gate = Lit::new(self.solver.new_var(), false);
+        self.solver.add_clause(&[!gate, left]);
+        self.solver.add_clause(&[!gate, right]);
+        self.solver.add_clause(&[gate, !left, !right]);
+        self.ands.insert((left, right), gate);
+
+        Bit::Lit(gate)
+    }
+
+    pub fn or(&mut self, left: Bit, right: Bit) -> Bit {
+        self.and(left.not(), right.not()).not()
+    }
+
+    pub fn xor(&mut self, left: Bit, right: Bit) -> Bit {
+        let (left, right) = match (left, right) {
+            (Bit::Const(constant), other) | (other, Bit::Const(constant)) => return if constant { other.not() } else { other },
+            (Bit::Lit(left), Bit::Lit(right)) => (left, right),
+        };
+        // Only plain variables are gated, the negations move to the result
+        let negated = left.is_negated() != right.is_negated();
+        let (left, right) = (Lit::new(left.var(), false), Lit::new(right.var(), false));
+        if left == right {
+            return Bit::Const(negated);
+        }
+        let key = (left.min(right), left.max(right));
+        let gate = match self.xors.get(&key) {
+            Some(gate) => *gate,
+            None => {
+                let gate = Lit::new(self.solver.new_var(), false);
+                self.solver.add_clause(&[!gate, left, right]);
+                self.solver.add_clause(&[!gate, !left, !right]);
+                self.solver.add_clause(&[gate, !left, right]);
+                self.solver.add_clause(&[gate, left, !right]);
+                self.xors.insert(key, gate);
+                gate
+            },
+        };
+
+        if negated { Bit::Lit(!gate) } else { Bit::Lit(gate) }
+    }
+
+    /// `then` when `condition` holds, `otherwise` when it doesn't
+    pub fn mux(&mut self, condition: Bit, then: Bit, otherwise: Bit) -> Bit {
+        match condition {
+            Bit::Const(true) => return then,
+            Bit::Const(false) => return otherwise,
+            _ if then == otherwise => return then,
+            _ => {},
+        }
+        let difference = self.xor(then, otherwise);
+        let flip = self.and(condition, difference);
+        self.xor(otherwise, flip)
+    }
+
+    pub fn constant(&self, value: u64, width: usize) -> BitVec {
+        (0..width).map(|bit| Bit::Const(bit < 64 && value >> bit & 1 == 1)).collect()
+    }
+
+    pub fn inputs(&mut self, width: usize) -> BitVec {
+        (0..width).map(|_| self.input()).collect()
+    }
+
+    pub fn xor_vec(&mut self, left: &[Bit], right: &[Bit]) -> BitVec {
+        left.iter().zip(right).map(|(left, right)| self.xor(*left, *right)).collect()
+    }
+
+    pub fn any(&mut self, bits: &[Bit]) -> Bit {
+        bits.iter().fold(Bit::Const(false), |any, bit| self.or(any, *bit))
+    }
+
+    pub fn all(&mut self, bits: &[Bit]) -> Bit {
+        bits.iter().fold(Bit::Const(true), |all, bit| self.and(all, *bit))
+    }
+
+    /// Whether the bits spell out `value`
+    pub fn equals(&mut self, bits: &[Bit], value: u64) -> Bit {
+        let matches = bits.iter().enumerate()
+            .map(|(index, bit)| if index < 64 && value >> index & 1 == 1 { *bit } else { bit.not() })
+            .collect::<Vec<_>>();
+        self.all(&matches)
+    }
+
+    /// `bits / 2^amount` for a known amount
+    pub fn shift_right(&self, bits: &[Bit], amount: usize) -> BitVec {
+        (0..bits.len()).map(|index| bits.get(index + amount).copied().unwrap_or(Bit::Const(false))).collect()
+    }
+
+    /// `bits / 2^amount`, a barrel shifter with a stage per bit of the amount, and whether 2^amount overflows the width
+    pub fn divide_pow2(&mut self, bits: &[Bit], amount: &[Bit]) -> (BitVec, Bit) {
+        let mut shifted = bits.to_vec();
+        let mut overflows = vec![];
+        for (stage, condition) in amount.iter().enumerate() {
+            match 1usize.checked_shl(stage as u32).filter(|shift| *shift < bits.len()) {
+                Some(shift) => {
+                    let moved = self.shift_right(&shifted, shift);
+                    shifted = shifted.iter().zip(moved).map(|(kept, moved)| self.mux(*condition, moved, *kept)).collect();
+                },
+                // Shifting by the width or more leaves nothing
+                None => overflows.push(*condition),
+            }
+        }
+        let overflow = self.any(&overflows);
+        let bits = shifted.iter().map(|bit| self.and(*bit, overflow.not())).collect();
+
+        (bits, overflow)
+    }
+
+    pub fn eval(&self, bit: Bit) -> bool {
+        match bit {
+            Bit::Const(value) => value,
+            Bit::Lit(lit) => self.solver.model_value(lit.var()) != lit.is_negated(),
+        }
+    }
+
+    /// The number the bits hold in the solver's last model
+    pub fn eval_vec(&self, bits: &[Bit]) -> u64 {
+        bits.iter().take(64).enumerate().fold(0, |value, (index, bit)| value | (self.eval(*bit) as u64) << index)
+    }
+}
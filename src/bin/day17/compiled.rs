@@ -0,0 +1,246 @@
+use miette::{miette, Result};
+use super::{budget::Budget, Computer, Instruction, Overflow};
+use super::register::Register;
+
+/// Reads a combo operand, resolved to a constant or a register when compiling
+type Operand<R> = Box<dyn Fn(&Computer<R>) -> R>;
+
+/// One decoded instruction, returning the digit it prints when it's an `out`
+type Op<R> = Box<dyn Fn(&mut Computer<R>) -> Result<Option<u8>>>;
+
+/// Instructions from a place a run can get to up to the next `jnz`, or the end of the program
+struct Block<R> {
+    ops: Vec<Op<R>>,
+    /// Target of the closing `jnz` when A isn't 0
+    jump: Option<usize>,
+    /// Where the run goes on otherwise
+    next: usize,
+}
+
+/// A program decoded once into closures, in blocks indexed by the instruction pointer they start at
+pub struct Compiled<R> {
+    blocks: Vec<Option<Block<R>>>,
+}
+
+impl<R: Register> Compiled<R> {
+    pub fn new(computer: &Computer<R>) -> Self {
+        let program = &computer.program;
+        let mut blocks = std::iter::repeat_with(|| None).take(program.len()).collect::<Vec<_>>();
+        let mut starts = vec![0];
+        while let Some(start) = starts.pop() {
+            if start >= program.len() || blocks[start].is_some() {
+                continue;
+            }
+            let block = Self::decode_block(program, start, computer.overflow);
+            starts.push(block.next);
+            starts.extend(block.jump);
+            blocks[start] = Some(block);
+        }
+
+        Self { blocks }
+    }
+
+    fn combo(combo: u8, instruction_pointer: usize) -> Result<Operand<R>, String> {
+        match combo {
+            0..=3 => {
+                let value = R::from_u8(combo);
+                Ok(Box::new(move |_| value.clone()))
+            },
+            4 => Ok(Box::new(|computer| computer.register_a.clone())),
+            5 => Ok(Box::new(|computer| computer.register_b.clone())),
+            6 => Ok(Box::new(|computer| computer.register_c.clone())),
+            _ => Err(format!("Invalid combo operand {combo} at {instruction_pointer}")),
+        }
+    }
+
+    /// `adv`, `bdv` and `cdv`, A divided by 2 to the power of the operand
+    fn divide(power: Operand<R>, into: fn(&mut Computer<R>) -> &mut R, overflow: Overflow, instruction_pointer: usize) -> Op<R> {
+        Box::new(move |computer| {
+            let power = power(computer);
+            let quotient = match overflow {
+                Overflow::Truncate => computer.register_a.div_pow2(&power),
+                Overflow::Strict => computer.register_a.checked_div_pow2(&power)
+                    .ok_or_else(|| miette!("Dividing by 2^{power} at {instruction_pointer} overflows"))?,
+            };
+            *into(computer) = quotient;
+            Ok(None)
+        })
+    }
+
+    fn decode(instruction: Instruction, literal: u8, overflow: Overflow, instruction_pointer: usize) -> Result<Op<R>, String> {
+        Ok(match instruction {
+            Instruction::Adv => Self::divide(Self::combo(literal, instruction_pointer)?, |computer| &mut computer.register_a, overflow, instruction_pointer),
+            Instruction::Bdv => Self::divide(Self::combo(literal, instruction_pointer)?, |computer| &mut computer.register_b, overflow, instruction_pointer),
+            Instruction::Cdv => Self::divide(Self::combo(literal, instruction_pointer)?, |computer| &mut computer.register_c, overflow, instruction_pointer),
+            Instruction::Bxl => {
+                let literal = R::from_u8(literal);
+                Box::new(move |computer| {
+                    computer.register_b = computer.register_b.xor(&literal);
+                    Ok(None)
+                })
+            },
+            Instruction::Bst => {
+                let value = Self::combo(literal, instruction_pointer)?;
+                Box::new(move |computer| {
+                    computer.register_b = R::from_u8(value(computer).low_bits());
+                    Ok(None)
+                })
+            },
+            Instruction::Bxc => Box::new(|computer| {
+                computer.register_b = computer.register_b.xor(&computer.register_c);
+                Ok(None)
+            }),
+            Instruction::Out => {
+                let value = Self::combo(literal, instruction_pointer)?;
+                Box::new(move |computer| Ok(Some(value(computer).low_bits())))
+            },
+            Instruction::Jnz => unreachable!("jnz closes its block instead of becoming an op"),
+        })
+    }
+
+    fn decode_block(program: &[u8], start: usize, overflow: Overflow) -> Block<R> {
+        let mut block = Block { ops: vec![], jump: None, next: program.len() };
+        let mut instruction_pointer = start;
+        while let Some(&opcode) = program.get(instruction_pointer) {
+            let decoded = Instruction::from_u8(opcode).map_err(|e| e.to_string()).and_then(|instruction| {
+                let literal = *program.get(instruction_pointer + 1)
+                    .ok_or_else(|| format!("Instruction at {instruction_pointer} has no operand"))?;
+                match instruction {
+                    Instruction::Jnz => {
+                        block.jump = Some(literal as usize);
+                        Ok(None)
+                    },
+                    instruction => Self::decode(instruction, literal, overflow, instruction_pointer).map(Some),
+                }
+            });
+
+            match decoded {
+                Ok(Some(op)) => block.ops.push(op),
+                Ok(None) => {
+                    block.next = instruction_pointer + 2;
+                    break;
+                },
+                Err(message) => {
+                    // An instruction that can't be decoded fails the run once it gets here
+                    block.ops.push(Box::new(move |_| Err(miette!("{message}"))));
+                    break;
+                },
+            }
+            instruction_pointer += 2;
+        }
+
+        block
+    }
+
+    /// Run the program on the computer's registers, stepping the budget and stopping like `Computer::run` does
+    pub fn run(&self, computer: &mut Computer<R>, stop_if: Option<&[u8]>, budget: &Budget) -> Result<()> {
+        let mut instruction_pointer = 0;
+        while let Some(Some(block)) = self.blocks.get(instruction_pointer) {
+            for op in &block.ops {
+                budget.step()?;
+                if let Some(digit) = op(computer)? {
+                    computer.output.push(digit);
+                    // The output matched so far, so only the new digit can stray
+                    if stop_if.is_some_and(|stop_if| stop_if.get(computer.output.len() - 1) != Some(&digit)) {
+                        return Ok(());
+                    }
+                }
+            }
+
+            instruction_pointer = match block.jump {
+                Some(jump) => {
+                    budget.step()?;
+                    if computer.register_a.is_zero() { block.next } else { jump }
+                },
+                None => block.next,
+            };
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::Compiled;
+    #[allow(unused_imports)]
+    use super::super::{budget::{Budget, Exhausted}, parse, Computer, Overflow};
+    #[allow(unused_imports)]
+    use super::super::property::check;
+    #[allow(unused_imports)]
+    use super::super::register::{BigUint, Register};
+
+    #[allow(dead_code)]
+    fn computer<R: Register>(a: &str, b: &str, program: &str) -> Computer<R> {
+        parse::<R>(&format!("Register A: {a}\nRegister B: {b}\nRegister C: 0\n\nProgram: {program}")).unwrap().1
+    }
+
+    /// Output or error message of both ways of running, with the same limits
+    #[allow(dead_code)]
+    fn both<R: Register>(computer: &Computer<R>, stop_if: Option<&[u8]>, budget: &Budget) -> [Result<Vec<u8>, String>; 2] {
+        let mut interpreted = computer.clone();
+        let interpreted = interpreted.run(stop_if, &budget.clone()).map(|_| interpreted.output).map_err(|e| e.to_string());
+        let mut compiled = computer.clone();
+        let compiled = Compiled::new(computer).run(&mut compiled, stop_if, budget).map(|_| compiled.output).map_err(|e| e.to_string());
+
+        [interpreted, compiled]
+    }
+
+    /// Every program the day's tests run, with the registers they run it on
+    #[allow(dead_code)]
+    const PROGRAMS: [(&str, &str, &str); 13] = [
+        ("729", "0", "0,1,5,4,3,0"),
+        ("2024", "0", "0,3,5,4,3,0"),
+        ("117440", "0", "0,3,5,4,3,0"),
+        ("2024", "0", "2,4,1,3,7,5,0,3,1,5,4,4,5,5,3,0"),
+        ("1", "70", "0,7"),
+        ("1", "70", "0,5"),
+        ("1", "70", "5"),
+        ("1", "70", "1,7,5,5"),
+        ("1", "0", "3,0"),
+        ("7", "130", "5,4,0,5,5,4"),
+        ("5902958103587056517120", "70", "0,5,5,4"),
+        ("10", "0", "3,3,5,4,0,1,3,3"),
+        ("2", "1", "3,4,5,5,5,4"),
+    ];
+
+    #[test]
+    fn test_matches_interpreter() {
+        let budget = Budget::unlimited().with_max_steps(10_000);
+        for (a, b, program) in PROGRAMS {
+            for overflow in [Overflow::Truncate, Overflow::Strict] {
+                let big = computer::<BigUint>(a, b, program).with_overflow(overflow);
+                let [interpreted, compiled] = both(&big, None, &budget);
+                assert_eq!(interpreted, compiled, "{program} with A = {a}, B = {b}");
+
+                if u128::parse(a).is_none() {
+                    continue;
+                }
+                let wide = computer::<u128>(a, b, program).with_overflow(overflow);
+                let [interpreted, compiled] = both(&wide, Some(&[4, 6, 3]), &budget);
+                assert_eq!(interpreted, compiled, "{program} with A = {a}, B = {b} stopping early");
+            }
+        }
+
+        let looping = computer::<u64>("1", "0", "3,0");
+        let error = Compiled::new(&looping).run(&mut looping.clone(), None, &budget).unwrap_err();
+        assert_eq!(error.downcast_ref::<Exhausted>(), Some(&Exhausted::Steps(10_000)));
+    }
+
+    #[test]
+    fn test_random_programs() {
+        check(
+            |rng| {
+                let program = (0..rng.below(16) + 1).map(|_| rng.below(8) as u8).collect::<Vec<_>>();
+                (program, rng.range(0..=1 << 40) as u64, rng.below(100) as u64)
+            },
+            |(program, a, b)| {
+                let program = program.iter().map(u8::to_string).collect::<Vec<_>>().join(",");
+                let computer = computer::<u64>(&a.to_string(), &b.to_string(), &program);
+                let [interpreted, compiled] = both(&computer, None, &Budget::unlimited().with_max_steps(1000));
+                interpreted == compiled
+            },
+        );
+    }
+}
@@ -1,9 +1,31 @@
 mod solution;
-use solution::Day15;
-use solution::Solution;
+#[path = "../../telemetry.rs"]
+mod telemetry;
+use solution::Day17;
+use solution::{Overflow, Solution};
+use solution::budget::{self, Budget};
+use miette::{miette, Result};
 
-fn main() {
+/// Set to `strict` to fail on divisions that overflow the registers instead of truncating them
+const OVERFLOW_VAR: &str = "AOC_OVERFLOW";
+
+/// From `AOC_OVERFLOW`, truncating when it isn't set
+fn overflow_from_env() -> Result<Overflow> {
+    match std::env::var(OVERFLOW_VAR).as_deref() {
+        Err(_) | Ok("truncate") => Ok(Overflow::Truncate),
+        Ok("strict") => Ok(Overflow::Strict),
+        Ok(other) => Err(miette!("{OVERFLOW_VAR}={other}: expected truncate or strict")),
+    }
+}
+
+fn main() -> Result<()> {
+    let _telemetry = telemetry::init();
+    budget::cancel_on_interrupt();
+    let budget = Budget::from_env()?;
     let input = include_str!("../../../inputs/day17.txt");
-    println!("Part 1: {:?}", Day15::part1(input));
-    println!("Part 2: {:?}", Day15::part2(input));
+    let parsed = Day17::parse(input)?.with_overflow(overflow_from_env()?);
+    println!("Part 1: {:?}", budget.run(|| Day17::part1(&parsed)));
+    println!("Part 2: {:?}", budget.run(|| Day17::part2(&parsed)));
+
+    Ok(())
 }
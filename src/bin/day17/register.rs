@@ -0,0 +1,225 @@
+use std::fmt::{self, Debug, Display};
+
+/// Value held by the computer's registers
+pub trait Register: Clone + Debug + Display + PartialEq + Eq + 'static {
+    fn from_u8(value: u8) -> Self;
+
+    /// A decimal number, None if it doesn't fit
+    fn parse(digits: &str) -> Option<Self>;
+
+    fn is_zero(&self) -> bool;
+
+    /// The lowest three bits, what `bst` and `out` keep
+    fn low_bits(&self) -> u8;
+
+    fn xor(&self, other: &Self) -> Self;
+
+    /// `self / 2^power`, which is 0 once the power is past the register's width
+    fn div_pow2(&self, power: &Self) -> Self;
+
+    /// `self / 2^power`, None when `2^power` itself doesn't fit in the register
+    fn checked_div_pow2(&self, power: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_register {
+    ($($t:ty),*) => {
+        $(impl Register for $t {
+            fn from_u8(value: u8) -> Self {
+                value.into()
+            }
+
+            fn parse(digits: &str) -> Option<Self> {
+                digits.parse().ok()
+            }
+
+            fn is_zero(&self) -> bool {
+                *self == 0
+            }
+
+            fn low_bits(&self) -> u8 {
+                (self % 8) as u8
+            }
+
+            fn xor(&self, other: &Self) -> Self {
+                self ^ other
+            }
+
+            fn div_pow2(&self, power: &Self) -> Self {
+                self.checked_div_pow2(power).unwrap_or(0)
+            }
+
+            fn checked_div_pow2(&self, power: &Self) -> Option<Self> {
+                u32::try_from(*power).ok().and_then(|power| self.checked_shr(power))
+            }
+        })*
+    };
+}
+
+impl_register!(u64, u128);
+
+/// Unsigned integer of any size, limbs from least to most significant without trailing zeros
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct BigUint(Vec<u64>);
+
+impl BigUint {
+    fn normalized(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self(limbs)
+    }
+
+    /// `self * factor + carry`
+    fn mul_add(&self, factor: u64, carry: u64) -> Self {
+        let mut carry = carry as u128;
+        let mut limbs = self.0.iter().map(|limb| {
+            let product = *limb as u128 * factor as u128 + carry;
+            carry = product >> 64;
+            product as u64
+        }).collect::<Vec<_>>();
+        limbs.push(carry as u64);
+
+        Self::normalized(limbs)
+    }
+
+    /// The quotient and remainder of dividing by a single limb
+    fn div_rem(&self, divisor: u64) -> (Self, u64) {
+        let mut remainder = 0u128;
+        let mut limbs = self.0.iter().rev().map(|limb| {
+            let dividend = remainder << 64 | *limb as u128;
+            remainder = dividend % divisor as u128;
+            (dividend / divisor as u128) as u64
+        }).collect::<Vec<_>>();
+        limbs.reverse();
+
+        (Self::normalized(limbs), remainder as u64)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::normalized(vec![value])
+    }
+}
+
+impl Register for BigUint {
+    fn from_u8(value: u8) -> Self {
+        (value as u64).into()
+    }
+
+    fn parse(digits: &str) -> Option<Self> {
+        digits.chars().try_fold(Self::default(), |number, digit| Some(number.mul_add(10, digit.to_digit(10)? as u64)))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn low_bits(&self) -> u8 {
+        self.0.first().map_or(0, |limb| (limb % 8) as u8)
+    }
+
+    fn xor(&self, other: &Self) -> Self {
+        let (long, short) = if self.0.len() >= other.0.len() { (self, other) } else { (other, self) };
+        let mut limbs = long.0.clone();
+        limbs.iter_mut().zip(&short.0).for_each(|(limb, other)| *limb ^= other);
+
+        Self::normalized(limbs)
+    }
+
+    fn div_pow2(&self, power: &Self) -> Self {
+        let shift = match power.0.as_slice() {
+            [] => Some(0),
+            [power] => usize::try_from(*power).ok(),
+            _ => None,
+        };
+        let Some(shift) = shift else { return Self::default() };
+        let (limbs, bits) = (shift / 64, shift % 64);
+        if limbs >= self.0.len() {
+            return Self::default();
+        }
+
+        let high = self.0.iter().skip(limbs + 1).map(Some).chain([None]);
+        Self::normalized(self.0[limbs..].iter().zip(high).map(|(low, high)| match (bits, high) {
+            (0, _) => *low,
+            (_, Some(high)) => low >> bits | high << (64 - bits),
+            (_, None) => low >> bits,
+        }).collect())
+    }
+
+    /// Every power of two fits in a big integer
+    fn checked_div_pow2(&self, power: &Self) -> Option<Self> {
+        Some(self.div_pow2(power))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Peel off 19 digits at a time, the most a limb holds
+        const CHUNK: u64 = 10u64.pow(19);
+        let mut chunks = vec![];
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.div_rem(CHUNK);
+            chunks.push(remainder);
+            rest = quotient;
+        }
+
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{first}")?;
+                rest.iter().rev().try_for_each(|chunk| write!(f, "{chunk:019}"))
+            },
+        }
+    }
+}
+
+impl Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{BigUint, Register};
+
+    #[allow(dead_code)]
+    fn big(digits: &str) -> BigUint {
+        BigUint::parse(digits).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let digits = "340282366920938463463374607431768211456";
+        assert_eq!(big(digits).to_string(), digits);
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(BigUint::parse("12a"), None);
+        assert_eq!(u64::parse("18446744073709551616"), None);
+    }
+
+    #[test]
+    fn test_div_pow2() {
+        // 2^128 + 2^64 + 7
+        let number = big("340282366920938463481821351505477763079");
+        assert_eq!(number.div_pow2(&big("64")).to_string(), "18446744073709551617");
+        assert_eq!(number.div_pow2(&big("1")).to_string(), "170141183460469231740910675752738881539");
+        assert_eq!(number.div_pow2(&big("129")), BigUint::default());
+        assert_eq!(number.div_pow2(&big("340282366920938463463374607431768211456")), BigUint::default());
+        assert_eq!(number.low_bits(), 7);
+
+        assert_eq!(u64::MAX.div_pow2(&63), 1);
+        assert_eq!(u64::MAX.div_pow2(&64), 0);
+        assert_eq!(u64::MAX.checked_div_pow2(&64), None);
+        assert_eq!(u128::MAX.checked_div_pow2(&64), Some(u64::MAX as u128));
+    }
+
+    #[test]
+    fn test_xor() {
+        assert_eq!(big("18446744073709551621").xor(&big("18446744073709551616")), big("5"));
+        assert_eq!(big("5").xor(&big("18446744073709551616")).to_string(), "18446744073709551621");
+    }
+}
@@ -0,0 +1,397 @@
+use std::{collections::BinaryHeap, ops::Not};
+use super::budget::{Budget, Exhausted};
+
+/// Conflicts before the first restart, every later restart waits half as long again
+const RESTART_FIRST: u64 = 100;
+const ACTIVITY_DECAY: f64 = 0.95;
+const ACTIVITY_LIMIT: f64 = 1e100;
+
+/// A variable or its negation, stored as `2 * var + negated`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit(u32);
+
+impl Lit {
+    pub fn new(var: u32, negated: bool) -> Self {
+        Self(var << 1 | negated as u32)
+    }
+
+    pub fn var(self) -> u32 {
+        self.0 >> 1
+    }
+
+    pub fn is_negated(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Lit {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(self.0 ^ 1)
+    }
+}
+
+/// CDCL solver with watched literals, first-UIP learning and activity ordered decisions
+#[derive(Debug, Default)]
+pub struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    /// Clauses per literal that watch it, the watches are the first two literals of a clause
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    /// Trail length at the start of every decision level
+    trail_limits: Vec<usize>,
+    propagated: usize,
+    activity: Vec<f64>,
+    increment: f64,
+    /// Unassigned variables by activity, entries whose activity changed since are skipped
+    order: BinaryHeap<(u64, u32)>,
+    /// The last value of every variable, decisions try it first
+    phases: Vec<bool>,
+    model: Vec<bool>,
+    unsatisfiable: bool,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self { increment: 1.0, ..Self::default() }
+    }
+
+    pub fn new_var(&mut self) -> u32 {
+        let var = self.values.len() as u32;
+        self.values.push(None);
+        self.levels.push(0);
+        self.reasons.push(None);
+        self.activity.push(0.0);
+        self.phases.push(false);
+        self.watches.extend([vec![], vec![]]);
+        self.order.push((0f64.to_bits(), var));
+
+        var
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.values[lit.var() as usize].map(|value| value != lit.is_negated())
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_limits.len()
+    }
+
+    /// Value of a variable in the last model found
+    pub fn model_value(&self, var: u32) -> bool {
+        self.model.get(var as usize).copied().unwrap_or(false)
+    }
+
+    pub fn add_clause(&mut self, lits: &[Lit]) {
+        if self.unsatisfiable {
+            return;
+        }
+        self.backtrack(0);
+
+        let mut clause = lits.to_vec();
+        clause.sort_unstable();
+        clause.dedup();
+        if clause.windows(2).any(|pair| pair[0] == !pair[1]) || clause.iter().any(|lit| self.value(*lit) == Some(true)) {
+            return;
+        }
+        clause.retain(|lit| self.value(*lit).is_none());
+
+        match clause.len() {
+            0 => self.unsatisfiable = true,
+            1 => {
+                self.enqueue(clause[0], None);
+                self.unsatisfiable = self.propagate().is_some();
+            },
+            _ => {
+                self.attach(clause);
+            },
+        }
+    }
+
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let index = self.clauses.len();
+        self.watches[clause[0].index()].push(index);
+        self.watches[clause[1].index()].push(index);
+        self.clauses.push(clause);
+
+        index
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var() as usize;
+        self.values[var] = Some(!lit.is_negated());
+        self.levels[var] = self.decision_level();
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Assign every unit literal, the clause that became false on a conflict
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let falsified = !self.trail[self.propagated];
+            self.propagated += 1;
+
+            let mut watching = std::mem::take(&mut self.watches[falsified.index()]);
+            let mut conflict = None;
+            let mut kept = 0;
+            for position in 0..watching.len() {
+                let index = watching[position];
+                if conflict.is_some() {
+                    watching[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+
+                let clause = &mut self.clauses[index];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.values[first.var() as usize].map(|value| value != first.is_negated()) == Some(true) {
+                    watching[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+
+                let values = &self.values;
+                let replacement = clause.iter().skip(2)
+                    .position(|lit| values[lit.var() as usize].map(|value| value != lit.is_negated()) != Some(false));
+                if let Some(offset) = replacement {
+                    clause.swap(1, offset + 2);
+                    let watch = clause[1];
+                    self.watches[watch.index()].push(index);
+                    continue;
+                }
+
+                watching[kept] = index;
+                kept += 1;
+                match self.value(first) {
+                    Some(false) => conflict = Some(index),
+                    _ => self.enqueue(first, Some(index)),
+                }
+            }
+            watching.truncate(kept);
+            self.watches[falsified.index()] = watching;
+
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+
+        None
+    }
+
+    fn bump(&mut self, var: u32) {
+        let var = var as usize;
+        self.activity[var] += self.increment;
+        if self.activity[var] > ACTIVITY_LIMIT {
+            self.activity.iter_mut().for_each(|activity| *activity /= ACTIVITY_LIMIT);
+            self.increment /= ACTIVITY_LIMIT;
+            self.order = (0..self.values.len() as u32)
+                .filter(|var| self.values[*var as usize].is_none())
+                .map(|var| (self.activity[var as usize].to_bits(), var))
+                .collect();
+        } else if self.values[var].is_none() {
+            self.order.push((self.activity[var].to_bits(), var as u32));
+        }
+    }
+
+    /// First-UIP clause learnt from a conflict, with the level to backtrack to
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let mut seen = vec![false; self.values.len()];
+        let mut learnt = vec![];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+        let mut skip = 0;
+        let asserting = loop {
+            for position in skip..self.clauses[clause].len() {
+                let lit = self.clauses[clause][position];
+                let var = lit.var() as usize;
+                if seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                seen[var] = true;
+                self.bump(lit.var());
+                if self.levels[var] == self.decision_level() {
+                    pending += 1;
+                } else {
+                    learnt.push(lit);
+                }
+            }
+
+            index -= 1;
+            while !seen[self.trail[index].var() as usize] {
+                index -= 1;
+            }
+            let lit = self.trail[index];
+            seen[lit.var() as usize] = false;
+            pending -= 1;
+            if pending == 0 {
+                break !lit;
+            }
+            // Reasons keep the literal they implied first
+            clause = self.reasons[lit.var() as usize].expect("Only decisions have no reason");
+            skip = 1;
+        };
+
+        let level = learnt.iter().map(|lit| self.levels[lit.var() as usize]).max().unwrap_or(0);
+        if let Some(position) = learnt.iter().position(|lit| self.levels[lit.var() as usize] == level) {
+            learnt.swap(0, position);
+        }
+        learnt.insert(0, asserting);
+
+        (learnt, level)
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        let Some(&limit) = self.trail_limits.get(level) else { return };
+        for lit in self.trail.drain(limit..) {
+            let var = lit.var() as usize;
+            self.phases[var] = !lit.is_negated();
+            self.values[var] = None;
+            self.reasons[var] = None;
+            self.order.push((self.activity[var].to_bits(), var as u32));
+        }
+        self.trail_limits.truncate(level);
+        self.propagated = limit;
+    }
+
+    fn pick_branch(&mut self) -> Option<Lit> {
+        while let Some((activity, var)) = self.order.pop() {
+            let index = var as usize;
+            if self.values[index].is_none() && activity == self.activity[index].to_bits() {
+                return Some(Lit::new(var, !self.phases[index]));
+            }
+        }
+
+        None
+    }
+
+    /// Whether the clauses are satisfiable with every assumption true, the model is kept on success
+    pub fn solve(&mut self, assumptions: &[Lit], budget: &Budget) -> Result<bool, Exhausted> {
+        if self.unsatisfiable {
+            return Ok(false);
+        }
+        let mut restart_at = RESTART_FIRST;
+        let mut conflicts = 0;
+
+        let satisfiable = loop {
+            if let Some(conflict) = self.propagate() {
+                budget.step()?;
+                conflicts += 1;
+                if self.decision_level() == 0 {
+                    self.unsatisfiable = true;
+                    break false;
+                }
+                let (learnt, level) = self.analyze(conflict);
+                self.backtrack(level);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let index = self.attach(learnt);
+                    self.enqueue(asserting, Some(index));
+                }
+                self.increment /= ACTIVITY_DECAY;
+                continue;
+            }
+
+            if conflicts >= restart_at {
+                restart_at += restart_at / 2;
+                conflicts = 0;
+                self.backtrack(0);
+                continue;
+            }
+
+            // Every assumption gets a level of its own, even when it already holds
+            if let Some(&assumption) = assumptions.get(self.decision_level()) {
+                let value = self.value(assumption);
+                if value == Some(false) {
+                    break false;
+                }
+                self.trail_limits.push(self.trail.len());
+                if value.is_none() {
+                    self.enqueue(assumption, None);
+                }
+                continue;
+            }
+
+            match self.pick_branch() {
+                Some(decision) => {
+                    self.trail_limits.push(self.trail.len());
+                    self.enqueue(decision, None);
+                },
+                None => break true,
+            }
+        };
+
+        if satisfiable {
+            self.model = self.values.iter().map(|value| value.unwrap_or(false)).collect();
+        }
+        self.backtrack(0);
+
+        Ok(satisfiable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lit, Solver};
+    #[allow(unused_imports)]
+    use super::super::budget::Budget;
+
+    #[allow(dead_code)]
+    fn pigeonhole(pigeons: u32, holes: u32) -> Solver {
+        let mut solver = Solver::new();
+        let var = |pigeon: u32, hole: u32| pigeon * holes + hole;
+        (0..pigeons * holes).for_each(|_| { solver.new_var(); });
+        for pigeon in 0..pigeons {
+            solver.add_clause(&(0..holes).map(|hole| Lit::new(var(pigeon, hole), false)).collect::<Vec<_>>());
+        }
+        for hole in 0..holes {
+            for first in 0..pigeons {
+                for second in first + 1..pigeons {
+                    solver.add_clause(&[Lit::new(var(first, hole), true), Lit::new(var(second, hole), true)]);
+                }
+            }
+        }
+
+        solver
+    }
+
+    #[test]
+    fn test_pigeonhole() {
+        let budget = Budget::unlimited();
+        assert!(!pigeonhole(6, 5).solve(&[], &budget).unwrap());
+
+        let mut solver = pigeonhole(5, 5);
+        assert!(solver.solve(&[], &budget).unwrap());
+        let holes = (0..5).map(|pigeon| (0..5).filter(|hole| solver.model_value(pigeon * 5 + hole)).collect::<Vec<_>>()).collect::<Vec<_>>();
+        assert!(holes.iter().all(|holes| holes.len() == 1));
+        assert!(holes.iter().flatten().collect::<std::collections::HashSet<_>>().len() == 5);
+    }
+
+    #[test]
+    fn test_assumptions() {
+        let budget = Budget::unlimited();
+        let mut solver = Solver::new();
+        let (a, b) = (Lit::new(solver.new_var(), false), Lit::new(solver.new_var(), false));
+        solver.add_clause(&[a, b]);
+        solver.add_clause(&[!a, b]);
+
+        assert!(!solver.solve(&[!b], &budget).unwrap());
+        assert!(solver.solve(&[a], &budget).unwrap());
+        assert!(solver.model_value(a.var()) && solver.model_value(b.var()));
+        solver.add_clause(&[!b]);
+        assert!(!solver.solve(&[], &budget).unwrap());
+    }
+}
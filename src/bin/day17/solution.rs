@@ -1,10 +1,38 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "register.rs"]
+pub mod register;
+#[path = "sat.rs"]
+pub mod sat;
+#[path = "bitvec.rs"]
+pub mod bitvec;
+#[path = "symbolic.rs"]
+pub mod symbolic;
+#[path = "compiled.rs"]
+pub mod compiled;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../property.rs"]
+mod property;
 use itertools::Itertools;
-use nom::{bytes::complete::tag, character::complete::{self, alpha1, newline}, multi::separated_list1, sequence::{delimited, pair, preceded, terminated}, IResult};
+use nom::{bytes::complete::tag, character::complete::{self, alpha1, digit1, newline}, combinator::map_opt, multi::separated_list1, sequence::{delimited, pair, preceded, terminated}, IResult};
 pub use utils::Solution;
-use miette::Result;
-pub struct Day15;
+use miette::{miette, Result};
+use budget::Budget;
+use register::{BigUint, Register};
+use symbolic::Search;
+use compiled::Compiled;
+pub struct Day17;
 
 #[derive(Debug)]
 enum Instruction {
@@ -19,8 +47,8 @@ enum Instruction {
 }
 
 impl Instruction {
-    fn from_u8(num: u8) -> Self {
-        match num {
+    fn from_u8(num: u8) -> Result<Self> {
+        Ok(match num {
             0 => Self::Adv,
             1 => Self::Bxl,
             2 => Self::Bst,
@@ -29,105 +57,131 @@ impl Instruction {
             5 => Self::Out,
             6 => Self::Bdv,
             7 => Self::Cdv,
-            _ => panic!("Invalid command"),
-        }
+            _ => return Err(miette!("Invalid opcode {num}")),
+        })
     }
 }
 
 
+/// What dividing by a power of two too large for the registers does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// The quotient is 0, as it would be with registers wide enough
+    #[default]
+    Truncate,
+    /// The run fails with the instruction pointer of the division
+    Strict,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Computer {
-    register_a: u64,
-    register_b: u64,
-    register_c: u64,
+pub struct Computer<R = u64> {
+    register_a: R,
+    register_b: R,
+    register_c: R,
     
     program: Vec<u8>,
     output: Vec<u8>,
+    overflow: Overflow,
 }
 
-impl Computer {
-    fn run(&mut self, stop_if: Option<&Vec<u8>>) {
+impl<R: Register> Computer<R> {
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn with_register_a(mut self, register_a: R) -> Self {
+        self.register_a = register_a;
+        self
+    }
+
+    /// Interpret the program one instruction at a time, `Compiled` has to match it on every program
+    pub fn run(&mut self, stop_if: Option<&[u8]>, budget: &Budget) -> Result<()> {
         let mut instruction_pointer = 0usize;
         loop {
             if instruction_pointer >= self.program.len() {
                 break;
             }
+            budget.step()?;
 
-            let current_instruction = self.program.get(instruction_pointer).unwrap();
-            let instruction = Instruction::from_u8(*current_instruction);
-            let literal = self.program.get(instruction_pointer + 1).unwrap();
-            let combo = self.get_combo(*literal);
+            let current_instruction = self.program[instruction_pointer];
+            let instruction = Instruction::from_u8(current_instruction)?;
+            let literal = *self.program.get(instruction_pointer + 1)
+                .ok_or_else(|| miette!("Instruction at {instruction_pointer} has no operand"))?;
             match instruction {
                 Instruction::Adv => {
-                    let num = self.register_a;
-                    let den = 2u32.pow(combo as u32) as u64;
-                    let res = num / den;
-                    self.register_a = res;
+                    self.register_a = self.divide(literal, instruction_pointer)?;
                 },
                 Instruction::Bxl => {
-                    self.register_b ^= *literal as u64;
+                    self.register_b = self.register_b.xor(&R::from_u8(literal));
                 },
                 Instruction::Bst => {
-                    self.register_b = combo % 8;
+                    self.register_b = R::from_u8(self.get_combo(literal, instruction_pointer)?.low_bits());
                 },
                 Instruction::Jnz => {
-                    if self.register_a != 0 {
-                        instruction_pointer = combo as usize;
+                    if !self.register_a.is_zero() {
+                        instruction_pointer = literal as usize;
                         continue;
                     }
                 },
                 Instruction::Bxc => {
-                    self.register_b ^= self.register_c;
+                    self.register_b = self.register_b.xor(&self.register_c);
                 },
                 Instruction::Out => {
-                    self.output.push((combo % 8) as u8);
+                    self.output.push(self.get_combo(literal, instruction_pointer)?.low_bits());
                 },
                 Instruction::Bdv => {
-                    let num = self.register_a;
-                    let den = 2u32.pow(combo as u32) as u64;
-                    let res = num / den;
-                    self.register_b = res;
+                    self.register_b = self.divide(literal, instruction_pointer)?;
                 },
                 Instruction::Cdv => {
-                    let num = self.register_a;
-                    let den = 2u32.pow(combo as u32) as u64;
-                    let res = num / den;
-                    self.register_c = res;
+                    self.register_c = self.divide(literal, instruction_pointer)?;
                 },
             };
 
             if let Some(stop_if) = stop_if {
-                if self.output != stop_if[0..self.output.len()] {
+                if stop_if.get(..self.output.len()) != Some(&self.output[..]) {
                     break;
                 }
             }
 
             instruction_pointer += 2;
         }
+
+        Ok(())
     }
 
-    fn get_combo(&self, combo: u8) -> u64 {
+    fn get_combo(&self, combo: u8, instruction_pointer: usize) -> Result<R> {
         match combo {
-            0..=3 => combo as u64,
-            4 => self.register_a,
-            5 => self.register_b,
-            6 => self.register_c,
-            _ => panic!("Invalid combo"),
+            0..=3 => Ok(R::from_u8(combo)),
+            4 => Ok(self.register_a.clone()),
+            5 => Ok(self.register_b.clone()),
+            6 => Ok(self.register_c.clone()),
+            _ => Err(miette!("Invalid combo operand {combo} at {instruction_pointer}")),
+        }
+    }
+
+    /// Register A divided by 2 to the power of the combo operand
+    fn divide(&self, combo: u8, instruction_pointer: usize) -> Result<R> {
+        let power = self.get_combo(combo, instruction_pointer)?;
+        match self.overflow {
+            Overflow::Truncate => Ok(self.register_a.div_pow2(&power)),
+            Overflow::Strict => self.register_a.checked_div_pow2(&power)
+                .ok_or_else(|| miette!("Dividing by 2^{power} at {instruction_pointer} overflows")),
         }
     }
 }
 
-fn parse_register(input: &str) -> IResult<&str, u64> {
+fn parse_register<R: Register>(input: &str) -> IResult<&str, R> {
     let (input, (_, register)) = terminated(pair(
         delimited(tag("Register "), alpha1, tag(": ")),
-        complete::u64
+        map_opt(digit1, R::parse)
     ), newline)(input)?;
 
     Ok((input, register))
 }
 
 
-fn parse(input: &str) -> IResult<&str, Computer> {
+fn parse<R: Register>(input: &str) -> IResult<&str, Computer<R>> {
     let (input, register_a) = parse_register(input)?;
     let (input, register_b) = parse_register(input)?;
     let (input, register_c) = parse_register(input)?;
@@ -140,43 +194,86 @@ fn parse(input: &str) -> IResult<&str, Computer> {
         register_c,
         program: instructions,
         output: vec![],
+        overflow: Overflow::default(),
     }))
 }
 
-impl Solution<String> for Day15 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<String> {
-        let (_, mut computer) = parse(input).unwrap();
-        computer.run(None);
+/// A parsed computer, with big registers only when the input's don't fit in 64 bits
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Width {
+    Narrow(Computer),
+    Big(Computer<BigUint>),
+}
+
+impl Width {
+    pub fn with_overflow(self, overflow: Overflow) -> Self {
+        match self {
+            Self::Narrow(computer) => Self::Narrow(computer.with_overflow(overflow)),
+            Self::Big(computer) => Self::Big(computer.with_overflow(overflow)),
+        }
+    }
 
-        dbg!(&computer);
+    /// The computer with 64-bit registers, None when they were too small for the input
+    pub fn narrow(&self) -> Option<&Computer> {
+        match self {
+            Self::Narrow(computer) => Some(computer),
+            Self::Big(_) => None,
+        }
+    }
 
-        Ok(computer.output.iter().map(|i| i.to_string()).collect_vec().join(","))
+    fn program(&self) -> &[u8] {
+        match self {
+            Self::Narrow(computer) => &computer.program,
+            Self::Big(computer) => &computer.program,
+        }
     }
-    
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<String> {
-        let (_, computer) = parse(input).unwrap();
+}
 
+/// Run a copy of the computer until it halts and join its output
+fn execute<R: Register>(computer: &Computer<R>) -> Result<String> {
+    let mut computer = computer.clone();
+    computer.run(None, &budget::current())?;
+    tracing::debug!(?computer, "halted");
 
-        let mut i = 0;
-        loop {
-            let mut computer_copy = computer.clone();
-            computer_copy.register_a = i;
-            computer_copy.run(Some(&computer.program.clone()));
+    Ok(computer.output.iter().map(|i| i.to_string()).collect_vec().join(","))
+}
 
-            if computer_copy.output == computer_copy.program {
-                break;
-            }
+impl Solution<String> for Day17 {
+    type Parsed = Width;
 
-            if i % 1_000_000 == 0 {
-                println!("{}", i);
-            }
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let (_, computer) = parse(input).map(|(rest, computer)| (rest, Width::Narrow(computer)))
+            .or_else(|_| parse(input).map(|(rest, computer)| (rest, Width::Big(computer))))
+            .map_err(|e| miette!("Failed to parse computer: {e}"))?;
+        if let Some(value) = computer.program().iter().find(|value| **value > 7) {
+            return Err(miette!("Program value {value} isn't a 3-bit number"));
+        }
+
+        Ok(computer)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(computer: &Self::Parsed) -> Result<String> {
+        match computer {
+            Width::Narrow(computer) => execute(computer),
+            Width::Big(computer) => execute(computer),
+        }
+    }
+    
+    #[tracing::instrument(skip_all)]
+    fn part2(computer: &Self::Parsed) -> Result<String> {
+        let computer = computer.narrow().ok_or(miette!("Searching register A needs registers B and C to fit in 64 bits"))?;
+        let mut search = Search::new(computer, &computer.program, &budget::current())?;
+        let a = search.minimum()?.ok_or(miette!("No value of register A makes the program print itself"))?;
 
-            i += 1;
+        let mut check = computer.clone().with_register_a(a);
+        Compiled::new(&check).run(&mut check, Some(&computer.program), &budget::current())?;
+        if check.output != computer.program {
+            return Err(miette!("Register A = {a} prints {:?} instead of the program", check.output));
         }
 
-        Ok(i.to_string())
+        Ok(a.to_string())
     }
 }
 
@@ -184,7 +281,94 @@ impl Solution<String> for Day15 {
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
-    use super::{Day15, Solution, parse, Computer};
+    use super::{Day17, Solution, parse, execute, Computer, Overflow};
+    #[allow(unused_imports)]
+    use super::register::{BigUint, Register};
+    #[allow(unused_imports)]
+    use super::budget::{Budget, Exhausted};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0"#, r#"Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 2,4,1,3,7,5,0,3,1,5,4,4,5,5,3,0"#], super::fuzz::solve::<Day17, _>);
+    }
+
+    #[test]
+    fn test_max_steps() {
+        let computer = Day17::parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0").unwrap();
+        let error = Budget::unlimited().with_max_steps(1000).run(|| Day17::part1(&computer)).unwrap_err();
+        assert_eq!(error.downcast_ref::<Exhausted>(), Some(&Exhausted::Steps(1000)));
+
+        // Halving A takes 64 rounds before every bit is gone
+        let computer = Day17::parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,3,0").unwrap();
+        let error = Budget::unlimited().with_max_steps(100).run(|| Day17::part2(&computer)).unwrap_err();
+        assert_eq!(error.downcast_ref::<Exhausted>(), Some(&Exhausted::Steps(100)));
+    }
+
+    #[test]
+    fn test_endless_loop() {
+        let computer = Day17::parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0").unwrap();
+        let error = Budget::unlimited().run(|| Day17::part2(&computer)).unwrap_err();
+        assert_eq!(error.downcast_ref::<Exhausted>(), None);
+    }
+
+    #[test]
+    fn test_jnz_literal() {
+        // A combo operand 4 would jump to A = 2 and print B first, the literal 4 jumps straight to the `out 4`
+        let computer = Day17::parse("Register A: 2\nRegister B: 1\nRegister C: 0\n\nProgram: 3,4,5,5,5,4").unwrap();
+        assert_eq!(Day17::part1(&computer).unwrap(), "2");
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        let run = |program: &str| Day17::part1(&Day17::parse(&format!("Register A: 1\nRegister B: 70\nRegister C: 0\n\nProgram: {program}")).unwrap());
+        assert!(Day17::parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 8,0").is_err());
+        assert!(run("0,7").is_err());
+        assert_eq!(run("0,5").unwrap(), "");
+        assert!(run("5").is_err());
+        assert_eq!(run("1,7,5,5").unwrap(), "1");
+    }
+
+    #[test]
+    fn test_register_widths() {
+        let input = |a: &str, b: &str| format!("Register A: {a}\nRegister B: {b}\nRegister C: 0\n\nProgram: 0,5,5,4");
+        let beyond_u64 = input("5902958103587056517120", "70");
+        let beyond_u128 = input("6805647338418769269267492148635364229120", "130");
+
+        assert!(parse::<u64>(&beyond_u64).is_err());
+        assert_eq!(execute(&parse::<u128>(&beyond_u64).unwrap().1).unwrap(), "5");
+        assert!(parse::<u128>(&beyond_u128).is_err());
+        assert_eq!(execute(&parse::<BigUint>(&beyond_u128).unwrap().1).unwrap(), "5");
+
+        let parsed = Day17::parse(&beyond_u128).unwrap();
+        assert!(parsed.narrow().is_none());
+        assert_eq!(Day17::part1(&parsed).unwrap(), "5");
+        assert!(Day17::part2(&parsed).is_err());
+        assert!(Day17::parse(&input("0", "1")).unwrap().narrow().is_some());
+    }
+
+    #[test]
+    fn test_strict_parts() {
+        let computer = Day17::parse("Register A: 7\nRegister B: 70\nRegister C: 0\n\nProgram: 5,4,0,5,5,4").unwrap();
+        assert_eq!(Day17::part1(&computer).unwrap(), "7,0");
+        assert!(Day17::part1(&computer.with_overflow(Overflow::Strict)).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let (_, computer) = parse::<u128>("Register A: 7\nRegister B: 130\nRegister C: 0\n\nProgram: 5,4,0,5,5,4").unwrap();
+        assert_eq!(execute(&computer).unwrap(), "7,0");
+
+        let error = execute(&computer.with_overflow(Overflow::Strict)).unwrap_err();
+        assert_eq!(error.to_string(), "Dividing by 2^130 at 2 overflows");
+    }
 
     #[test]
     fn test_parse() {
@@ -193,7 +377,7 @@ Register B: 0
 Register C: 0
 
 Program: 0,1,5,4,3,0"#;
-        let (input, result) = parse(test).unwrap();
+        let (input, result) = parse::<u64>(test).unwrap();
         assert_eq!(input, "");
         assert_eq!(result, Computer {
             register_a: 729,
@@ -201,6 +385,7 @@ Program: 0,1,5,4,3,0"#;
             register_c: 0,
             program: vec![0,1,5,4,3,0],
             output: vec![],
+            overflow: Overflow::Truncate,
         })
     }
 
@@ -211,7 +396,7 @@ Register B: 0
 Register C: 0
 
 Program: 0,1,5,4,3,0"#;
-        let result = Day15::part1(test);
+        let result = Day17::part1(&Day17::parse(test).unwrap());
         assert_eq!(result.unwrap(), "4,6,3,5,6,3,5,2,1,0")
     }
 
@@ -222,7 +407,7 @@ Register B: 0
 Register C: 0
 
 Program: 0,3,5,4,3,0"#;
-        let result = Day15::part2(test);
+        let result = Day17::part2(&Day17::parse(test).unwrap());
         assert_eq!(result.unwrap(), "117440")
     }
 }
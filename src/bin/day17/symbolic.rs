@@ -0,0 +1,331 @@
+use miette::Result;
+use super::{budget::Budget, Computer, Instruction, Overflow};
+use super::bitvec::{Bit, BitVec, Circuit};
+use super::sat::Lit;
+
+/// Bits of every register, the same as the `u64` registers of a parsed computer
+const WIDTH: usize = 64;
+
+#[derive(Debug, Clone)]
+struct State {
+    registers: [BitVec; 3],
+    instruction_pointer: usize,
+    output: Vec<BitVec>,
+    /// What has to hold for register A to take this way through the program
+    condition: Bit,
+    /// Where every jump since the last output went and the registers it went there with
+    jumps: Vec<(usize, [BitVec; 3])>,
+}
+
+/// A way through the program that halts, with its 3-bit output digits as formulas over register A
+#[derive(Debug, Clone)]
+pub struct Path {
+    pub condition: Bit,
+    pub output: Vec<BitVec>,
+}
+
+/// A computer run with register A unknown
+#[derive(Debug, Default)]
+pub struct Symbolic {
+    pub circuit: Circuit,
+    pub register_a: BitVec,
+}
+
+impl Symbolic {
+    pub fn new() -> Self {
+        let mut circuit = Circuit::new();
+        let register_a = circuit.inputs(WIDTH);
+
+        Self { circuit, register_a }
+    }
+
+    fn combo(&self, state: &State, combo: u8) -> Option<BitVec> {
+        match combo {
+            0..=3 => Some(self.circuit.constant(combo as u64, WIDTH)),
+            4..=6 => Some(state.registers[combo as usize - 4].clone()),
+            _ => None,
+        }
+    }
+
+    /// Every path through the program that halts after printing at most `max_output` digits, only those printing
+    /// `target` when there is one. Paths that fail on a concrete A, like ones reaching an invalid combo, are left out
+    pub fn paths(&mut self, computer: &Computer, max_output: usize, target: Option<&[u8]>, budget: &Budget) -> Result<Vec<Path>> {
+        let program = &computer.program;
+        let mut paths = vec![];
+        let mut stack = vec![State {
+            registers: [
+                self.register_a.clone(),
+                self.circuit.constant(computer.register_b, WIDTH),
+                self.circuit.constant(computer.register_c, WIDTH),
+            ],
+            instruction_pointer: 0,
+            output: vec![],
+            condition: Bit::Const(true),
+            jumps: vec![],
+        }];
+
+        while let Some(mut state) = stack.pop() {
+            let ip = state.instruction_pointer;
+            let Some(&opcode) = program.get(ip) else {
+                if target.is_none_or(|target| state.output.len() == target.len()) {
+                    paths.push(Path { condition: state.condition, output: state.output });
+                }
+                continue;
+            };
+            budget.step()?;
+            let (Ok(instruction), Some(&literal)) = (Instruction::from_u8(opcode), program.get(ip + 1)) else { continue };
+
+            state.instruction_pointer += 2;
+            match instruction {
+                Instruction::Adv | Instruction::Bdv | Instruction::Cdv => {
+                    let Some(power) = self.combo(&state, literal) else { continue };
+                    let (quotient, overflow) = self.circuit.divide_pow2(&state.registers[0], &power);
+                    if computer.overflow == Overflow::Strict {
+                        state.condition = self.circuit.and(state.condition, overflow.not());
+                    }
+                    let register = match instruction {
+                        Instruction::Adv => 0,
+                        Instruction::Bdv => 1,
+                        _ => 2,
+                    };
+                    state.registers[register] = quotient;
+                },
+                Instruction::Bxl => {
+                    let literal = self.circuit.constant(literal as u64, WIDTH);
+                    state.registers[1] = self.circuit.xor_vec(&state.registers[1], &literal);
+                },
+                Instruction::Bst => {
+                    let Some(value) = self.combo(&state, literal) else { continue };
+                    state.registers[1] = value.iter().take(3).copied().chain([Bit::Const(false); WIDTH - 3]).collect();
+                },
+                Instruction::Jnz => {
+                    let nonzero = self.circuit.any(&state.registers[0]);
+                    let jump = self.circuit.and(state.condition, nonzero);
+                    let fall_through = self.circuit.and(state.condition, nonzero.not());
+                    if fall_through != Bit::Const(false) {
+                        stack.push(State { condition: fall_through, ..state.clone() });
+                    }
+                    // Back where an earlier jump went with the same registers and nothing printed since, the program
+                    // would go round the same way forever
+                    let target = (literal as usize, state.registers.clone());
+                    if jump != Bit::Const(false) && !state.jumps.contains(&target) {
+                        state.jumps.push(target);
+                        stack.push(State { condition: jump, instruction_pointer: literal as usize, ..state });
+                    }
+                    continue;
+                },
+                Instruction::Bxc => {
+                    state.registers[1] = self.circuit.xor_vec(&state.registers[1], &state.registers[2]);
+                },
+                Instruction::Out => {
+                    let Some(value) = self.combo(&state, literal) else { continue };
+                    let digit = value[..3].to_vec();
+                    if state.output.len() == max_output {
+                        continue;
+                    }
+                    if let Some(&expected) = target.and_then(|target| target.get(state.output.len())) {
+                        let matches = self.circuit.equals(&digit, expected as u64);
+                        state.condition = self.circuit.and(state.condition, matches);
+                    }
+                    state.output.push(digit);
+                    state.jumps.clear();
+                },
+            }
+
+            if state.condition != Bit::Const(false) {
+                stack.push(state);
+            }
+        }
+
+        Ok(paths)
+    }
+}
+
+/// Every value of register A that makes the computer print `target`
+#[derive(Debug)]
+pub struct Search {
+    symbolic: Symbolic,
+    budget: Budget,
+    exhausted: bool,
+}
+
+impl Search {
+    pub fn new(computer: &Computer, target: &[u8], budget: &Budget) -> Result<Self> {
+        let mut symbolic = Symbolic::new();
+        let paths = symbolic.paths(computer, target.len(), Some(target), budget)?;
+        tracing::debug!(paths = paths.len(), digits = paths.iter().map(|path| path.output.len()).sum::<usize>(), "explored");
+        let conditions = paths.iter().map(|path| path.condition).collect::<Vec<_>>();
+        match symbolic.circuit.any(&conditions) {
+            Bit::Const(true) => {},
+            Bit::Const(false) => symbolic.circuit.solver.add_clause(&[]),
+            Bit::Lit(lit) => symbolic.circuit.solver.add_clause(&[lit]),
+        }
+
+        Ok(Self { symbolic, budget: budget.clone(), exhausted: false })
+    }
+
+    /// Literals of register A, least significant first
+    fn a_literals(&self) -> Vec<Lit> {
+        self.symbolic.register_a.iter().filter_map(|bit| match bit {
+            Bit::Lit(lit) => Some(*lit),
+            Bit::Const(_) => None,
+        }).collect()
+    }
+
+    /// The smallest A, found by fixing its bits from the top and keeping each at 0 when that is still satisfiable
+    pub fn minimum(&mut self) -> Result<Option<u64>> {
+        let solver = &mut self.symbolic.circuit.solver;
+        if !solver.solve(&[], &self.budget)? {
+            return Ok(None);
+        }
+
+        let mut assumptions = vec![];
+        for lit in self.a_literals().into_iter().rev() {
+            let solver = &mut self.symbolic.circuit.solver;
+            assumptions.push(!lit);
+            if !solver.solve(&assumptions, &self.budget)? {
+                assumptions.pop();
+                assumptions.push(lit);
+            }
+        }
+        self.symbolic.circuit.solver.solve(&assumptions, &self.budget)?;
+
+        Ok(Some(self.symbolic.circuit.eval_vec(&self.symbolic.register_a)))
+    }
+}
+
+impl Iterator for Search {
+    type Item = Result<u64>;
+
+    /// Another A, every one found is ruled out for the next
+    fn next(&mut self) -> Option<Result<u64>> {
+        if self.exhausted {
+            return None;
+        }
+        match self.symbolic.circuit.solver.solve(&[], &self.budget) {
+            Ok(true) => {},
+            Ok(false) => {
+                self.exhausted = true;
+                return None;
+            },
+            Err(error) => {
+                self.exhausted = true;
+                return Some(Err(error.into()));
+            },
+        }
+
+        let circuit = &self.symbolic.circuit;
+        let value = circuit.eval_vec(&self.symbolic.register_a);
+        let blocking = self.a_literals().into_iter()
+            .map(|lit| if circuit.eval(Bit::Lit(lit)) { !lit } else { lit })
+            .collect::<Vec<_>>();
+        self.symbolic.circuit.solver.add_clause(&blocking);
+
+        Some(Ok(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Search, Symbolic};
+    #[allow(unused_imports)]
+    use super::super::{budget::Budget, execute, parse, Computer, Overflow};
+    #[allow(unused_imports)]
+    use super::super::bitvec::Bit;
+
+    #[allow(dead_code)]
+    fn computer(a: u64, program: &str) -> Computer {
+        parse::<u64>(&format!("Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: {program}")).unwrap().1
+    }
+
+    #[allow(dead_code)]
+    fn digits(output: &str) -> Vec<u8> {
+        output.split(',').filter(|digit| !digit.is_empty()).map(|digit| digit.parse().unwrap()).collect()
+    }
+
+    /// Shaped like the puzzle inputs, a loop that prints a digit from A's low bits mixed with higher ones
+    #[allow(dead_code)]
+    const PROGRAM: &str = "2,4,1,3,7,5,0,3,1,5,4,4,5,5,3,0";
+
+    #[test]
+    fn test_quine() {
+        let computer = computer(2024, "0,3,5,4,3,0");
+        let mut solutions = Search::new(&computer, &computer.program, &Budget::unlimited()).unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        solutions.sort_unstable();
+        assert_eq!(solutions, (117440..=117447).collect::<Vec<_>>());
+
+        let mut search = Search::new(&computer, &computer.program, &Budget::unlimited()).unwrap();
+        assert_eq!(search.minimum().unwrap(), Some(117440));
+        assert_eq!(search.minimum().unwrap(), Some(117440));
+    }
+
+    #[test]
+    fn test_any_target() {
+        for (program, a) in [("0,1,5,4,3,0", 729), (PROGRAM, 2024), (PROGRAM, 0o7_1234_5670), (PROGRAM, 1 << 40 | 12345)] {
+            let target = digits(&execute(&computer(a, program)).unwrap());
+            let solutions = Search::new(&computer(0, program), &target, &Budget::unlimited()).unwrap()
+                .take(64)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            assert!(solutions.contains(&a), "{a} missing from {solutions:?} for {program}");
+            for solution in solutions {
+                assert_eq!(digits(&execute(&computer(solution, program)).unwrap()), target, "A = {solution} for {program}");
+            }
+        }
+
+        let mut search = Search::new(&computer(0, PROGRAM), &[7; 20], &Budget::unlimited()).unwrap();
+        let found = search.next().transpose().unwrap();
+        assert!(found.is_none_or(|a| digits(&execute(&computer(a, PROGRAM)).unwrap()) == [7; 20]));
+    }
+
+    #[test]
+    fn test_minimum_matches_brute_force() {
+        for a in [5, 100, 2024] {
+            let target = digits(&execute(&computer(a, PROGRAM)).unwrap());
+            let smallest = (0..=a).find(|a| digits(&execute(&computer(*a, PROGRAM)).unwrap()) == target);
+            let mut search = Search::new(&computer(0, PROGRAM), &target, &Budget::unlimited()).unwrap();
+            assert_eq!(search.minimum().unwrap(), smallest);
+        }
+    }
+
+    #[test]
+    fn test_output_formulas() {
+        let program = computer(0, PROGRAM);
+        let mut symbolic = Symbolic::new();
+        let paths = symbolic.paths(&program, 4, None, &Budget::unlimited()).unwrap();
+        assert_eq!(paths.len(), 4);
+
+        for a in [1, 8, 300, 4000] {
+            let assumptions = symbolic.register_a.iter().enumerate()
+                .map(|(bit, a_bit)| match a_bit {
+                    Bit::Lit(lit) => if a >> bit & 1 == 1 { *lit } else { !*lit },
+                    Bit::Const(_) => unreachable!(),
+                })
+                .collect::<Vec<_>>();
+            assert!(symbolic.circuit.solver.solve(&assumptions, &Budget::unlimited()).unwrap());
+
+            let path = paths.iter().find(|path| symbolic.circuit.eval(path.condition)).unwrap();
+            let output = path.output.iter().map(|digit| symbolic.circuit.eval_vec(digit) as u8).collect::<Vec<_>>();
+            assert_eq!(output, digits(&execute(&computer(a, PROGRAM)).unwrap()), "A = {a}");
+        }
+    }
+
+    #[test]
+    fn test_strict_overflow() {
+        // Prints A / 2^A, always 0, but 2^A overflows from A = 64 on
+        let program = computer(0, "7,0,0,6,5,4");
+        let truncated = Search::new(&program, &[0], &Budget::unlimited()).unwrap().take(100).count();
+        assert_eq!(truncated, 100);
+
+        let strict = program.with_overflow(Overflow::Strict);
+        let mut solutions = Search::new(&strict, &[0], &Budget::unlimited()).unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        solutions.sort_unstable();
+        assert_eq!(solutions, (0..64).collect::<Vec<_>>());
+    }
+}
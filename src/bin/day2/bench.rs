@@ -0,0 +1,26 @@
+mod solution;
+use solution::Day2;
+use solution::Solution;
+
+const INPUT: &str = include_str!("../../../inputs/day2.txt");
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day2::parse(divan::black_box(INPUT)));
+}
+
+#[divan::bench]
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day2::parse(INPUT).unwrap();
+    bencher.bench(|| Day2::part1(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day2::parse(INPUT).unwrap();
+    bencher.bench(|| Day2::part2(divan::black_box(&parsed)));
+}
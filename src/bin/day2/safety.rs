@@ -0,0 +1,176 @@
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+impl Direction {
+    fn of(from: u32, to: u32) -> Self {
+        if to > from { Direction::Ascending } else { Direction::Descending }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The level goes the other way than the first two levels that differ
+    DirectionChange(Direction),
+    /// The difference to the previous level is outside the step range
+    StepSize(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    /// First level that breaks the rules in the unmodified report
+    Unsafe { index: usize, reason: Reason },
+    /// Safe once the levels at these indices are removed
+    Dampened { removed: Vec<usize> },
+}
+
+impl Verdict {
+    pub fn is_safe(&self) -> bool {
+        !matches!(self, Verdict::Unsafe { .. })
+    }
+}
+
+/// Checks reports against a step range, allowing up to `tolerance` levels to be removed
+#[derive(Debug, Clone)]
+pub struct Analyser {
+    steps: RangeInclusive<u32>,
+    tolerance: usize,
+}
+
+impl Default for Analyser {
+    fn default() -> Self {
+        Self { steps: 1..=3, tolerance: 0 }
+    }
+}
+
+impl Analyser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_steps(mut self, steps: RangeInclusive<u32>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: usize) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The direction is set by the first two levels that differ
+    fn first_violation(&self, levels: &[u32]) -> Option<(usize, Reason)> {
+        let mut direction = None;
+        levels.windows(2).enumerate().find_map(|(i, pair)| {
+            let diff = pair[0].abs_diff(pair[1]);
+            if !self.steps.contains(&diff) {
+                return Some((i + 1, Reason::StepSize(diff)));
+            }
+            if diff == 0 {
+                return None;
+            }
+            match *direction.get_or_insert(Direction::of(pair[0], pair[1])) {
+                expected if expected != Direction::of(pair[0], pair[1]) => Some((i + 1, Reason::DirectionChange(expected))),
+                _ => None,
+            }
+        })
+    }
+
+    fn valid_step(&self, from: u32, to: u32, direction: Direction) -> bool {
+        self.steps.contains(&from.abs_diff(to)) && (from == to || Direction::of(from, to) == direction)
+    }
+
+    /// Fewest levels to remove so the rest follows `direction`, in a single pass where every level
+    /// only looks back `tolerance + 1` levels for the previous one that is kept
+    fn fewest_removals(&self, levels: &[u32], direction: Direction) -> Option<Vec<usize>> {
+        // Removals needed when level i is the last one kept, and the previous kept level
+        let mut best: Vec<(usize, Option<usize>)> = Vec::with_capacity(levels.len());
+        for (i, level) in levels.iter().enumerate() {
+            let window = i.saturating_sub(self.tolerance + 1)..i;
+            let entry = window
+                .filter(|j| self.valid_step(levels[*j], *level, direction))
+                .map(|j| (best[j].0 + i - j - 1, Some(j)))
+                .fold((i, None), |min, entry| if entry.0 < min.0 { entry } else { min });
+            best.push(entry);
+        }
+
+        let (removals, mut last) = best.iter().enumerate()
+            .map(|(i, (removals, _))| (removals + levels.len() - 1 - i, Some(i)))
+            .min_by_key(|(removals, _)| *removals)?;
+        if removals > self.tolerance {
+            return None;
+        }
+
+        let mut kept = vec![false; levels.len()];
+        while let Some(i) = last {
+            kept[i] = true;
+            last = best[i].1;
+        }
+
+        Some(kept.iter().enumerate().filter(|(_, kept)| !**kept).map(|(i, _)| i).collect())
+    }
+
+    pub fn analyse(&self, levels: &[u32]) -> Verdict {
+        let Some((index, reason)) = self.first_violation(levels) else {
+            return Verdict::Safe;
+        };
+        if self.tolerance == 0 {
+            return Verdict::Unsafe { index, reason };
+        }
+
+        [Direction::Ascending, Direction::Descending].into_iter()
+            .filter_map(|direction| self.fewest_removals(levels, direction))
+            .min_by_key(|removed| removed.len())
+            .map_or(Verdict::Unsafe { index, reason }, |removed| Verdict::Dampened { removed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Analyser, Direction, Reason, Verdict};
+
+    #[test]
+    fn test_verdicts() {
+        let analyser = Analyser::new();
+        assert_eq!(analyser.analyse(&[7, 6, 4, 2, 1]), Verdict::Safe);
+        assert_eq!(analyser.analyse(&[1, 2, 7, 8, 9]), Verdict::Unsafe { index: 2, reason: Reason::StepSize(5) });
+        assert_eq!(analyser.analyse(&[1, 3, 2, 4, 5]), Verdict::Unsafe { index: 2, reason: Reason::DirectionChange(Direction::Ascending) });
+        assert_eq!(analyser.analyse(&[8, 6, 4, 4, 1]), Verdict::Unsafe { index: 3, reason: Reason::StepSize(0) });
+
+        let analyser = Analyser::new().with_tolerance(1);
+        assert_eq!(analyser.analyse(&[1, 3, 2, 4, 5]), Verdict::Dampened { removed: vec![2] });
+        assert_eq!(analyser.analyse(&[8, 6, 4, 4, 1]), Verdict::Dampened { removed: vec![3] });
+        assert!(!analyser.analyse(&[9, 7, 6, 2, 1]).is_safe());
+        // Removing the first level flips the direction of the report
+        assert_eq!(analyser.analyse(&[5, 1, 2, 3, 4]), Verdict::Dampened { removed: vec![0] });
+    }
+
+    #[test]
+    fn test_tolerance_and_steps() {
+        let levels = [1, 9, 2, 8, 3, 4];
+        assert!(!Analyser::new().with_tolerance(1).analyse(&levels).is_safe());
+        assert_eq!(Analyser::new().with_tolerance(2).analyse(&levels), Verdict::Dampened { removed: vec![1, 3] });
+        assert_eq!(Analyser::new().with_steps(1..=8).analyse(&[1, 9, 10]), Verdict::Safe);
+        assert_eq!(Analyser::new().with_steps(0..=1).analyse(&[3, 3, 2]), Verdict::Safe);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let analyser = Analyser::new().with_tolerance(1);
+        let reports: [&[u32]; 7] = [&[1, 2], &[4, 4, 4], &[1, 5, 2, 3], &[3, 1, 2, 3, 4], &[1, 2, 3, 9], &[9, 1, 2, 3], &[6, 5, 7, 8, 4]];
+        reports.iter().for_each(|levels| {
+            let brute = (0..levels.len()).any(|i| {
+                let mut levels = levels.to_vec();
+                levels.remove(i);
+                Analyser::new().analyse(&levels) == Verdict::Safe
+            });
+            assert_eq!(analyser.analyse(levels).is_safe(), brute, "{levels:?}");
+        });
+    }
+}
@@ -0,0 +1,107 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "safety.rs"]
+pub mod safety;
+
+pub use utils::Solution;
+use miette::{IntoDiagnostic, Result};
+use rayon::prelude::*;
+use budget::Exhausted;
+use safety::Analyser;
+use std::ops::RangeInclusive;
+pub struct Day2;
+
+/// Adjacent levels of a safe report differ by at least one and at most three
+const SAFE_STEPS: RangeInclusive<u32> = 1..=3;
+
+pub fn parse(input: &str) -> Result<Vec<Vec<u32>>> {
+    input
+        .trim()
+        .lines()
+        .map(|line| line
+            .split_whitespace()
+            .map(|word| word.parse::<u32>().into_diagnostic())
+            .collect())
+        .collect()
+}
+
+/// A step per report, the part's budget is shared with the rayon workers
+fn count_safe(reports: &[Vec<u32>], analyser: &Analyser) -> Result<u32> {
+    let budget = budget::current();
+    Ok(reports
+        .par_iter()
+        .map(|levels| {
+            budget.step()?;
+            Ok(analyser.analyse(levels).is_safe() as u32)
+        })
+        .try_reduce(|| 0, |a, b| Ok::<_, Exhausted>(a + b))?)
+}
+
+impl Solution<u32> for Day2 {
+    type Parsed = Vec<Vec<u32>>;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse(input)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(reports: &Self::Parsed) -> Result<u32> {
+        count_safe(reports, &Analyser::new().with_steps(SAFE_STEPS))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2(reports: &Self::Parsed) -> Result<u32> {
+        count_safe(reports, &Analyser::new().with_steps(SAFE_STEPS).with_tolerance(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Day2, Solution};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9"#], super::fuzz::solve::<Day2, _>);
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9"#;
+        let result = Day2::part1(&Day2::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 2)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9"#;
+        let result = Day2::part2(&Day2::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 4)
+    }
+}
@@ -0,0 +1,60 @@
+mod solution;
+use solution::Day3;
+use solution::Solution;
+use solution::instruction::InstructionSet;
+use solution::{nom_tokenizer::NomTokenizer, regex_tokenizer::RegexTokenizer, run, run_stream};
+use miette::Result;
+
+const INPUT: &str = include_str!("../../../inputs/day3.txt");
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day3::parse(divan::black_box(INPUT)));
+}
+
+#[divan::bench]
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day3::parse(INPUT).unwrap();
+    bencher.bench(|| Day3::part1(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day3::parse(INPUT).unwrap();
+    bencher.bench(|| Day3::part2(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part1_nom() -> u64 {
+    let set = InstructionSet::part1();
+    run(divan::black_box(INPUT), &set, &NomTokenizer::new(&set))
+}
+
+#[divan::bench]
+fn bench_part2_nom() -> u64 {
+    let set = InstructionSet::part2();
+    run(divan::black_box(INPUT), &set, &NomTokenizer::new(&set))
+}
+
+/// The part 2 set with an `add` and a nested `do()` scope, for what extra instructions cost
+#[divan::bench]
+fn bench_part2_custom() -> u64 {
+    let set = InstructionSet::part2()
+        .with("add", 2, |machine, args| {
+            if machine.enabled {
+                machine.total += (args[0] + args[1]) as u64;
+            }
+        })
+        .with("begin", 0, |machine, _| machine.stack.push(machine.enabled))
+        .with("end", 0, |machine, _| machine.enabled = machine.stack.pop().unwrap_or(true));
+    run(divan::black_box(INPUT), &set, &RegexTokenizer::new(&set))
+}
+
+#[divan::bench]
+fn bench_part2_stream() -> Result<u64> {
+    run_stream(divan::black_box(INPUT).as_bytes(), &InstructionSet::part2())
+}
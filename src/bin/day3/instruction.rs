@@ -0,0 +1,114 @@
+/// Instruction found in the corrupted memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+    /// Instruction added to an `InstructionSet` with `InstructionSet::with`
+    Custom(&'static str, Vec<u32>),
+}
+
+/// Runs a custom instruction with its arguments
+pub type Handler = fn(&mut Machine, &[u32]);
+
+/// Name and argument count of an instruction, every argument is a number of 1 to 3 digits
+#[derive(Debug, Clone, Copy)]
+pub struct Spec {
+    pub name: &'static str,
+    pub arity: usize,
+    handler: Option<Handler>,
+}
+
+impl Spec {
+    /// The instruction for the arguments, None if the count doesn't match
+    pub fn instruction(&self, args: Vec<u32>) -> Option<Instruction> {
+        if args.len() != self.arity {
+            return None;
+        }
+
+        Some(match (self.handler, self.name, args.as_slice()) {
+            (None, "mul", [a, b]) => Instruction::Mul(*a, *b),
+            (None, "do", []) => Instruction::Do,
+            (None, "don't", []) => Instruction::Dont,
+            _ => Instruction::Custom(self.name, args),
+        })
+    }
+}
+
+/// Instructions the tokenizers look for
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    specs: Vec<Spec>,
+}
+
+impl InstructionSet {
+    pub fn part1() -> Self {
+        Self { specs: vec![Spec { name: "mul", arity: 2, handler: None }] }
+    }
+
+    pub fn part2() -> Self {
+        let mut set = Self::part1();
+        set.specs.push(Spec { name: "do", arity: 0, handler: None });
+        set.specs.push(Spec { name: "don't", arity: 0, handler: None });
+        set
+    }
+
+    /// Add an instruction that is tokenized as `Instruction::Custom` and run by `handler`
+    #[allow(dead_code)]
+    pub fn with(mut self, name: &'static str, arity: usize, handler: Handler) -> Self {
+        self.specs.push(Spec { name, arity, handler: Some(handler) });
+        self
+    }
+
+    pub fn specs(&self) -> &[Spec] {
+        &self.specs
+    }
+
+    fn handler(&self, name: &str) -> Option<Handler> {
+        self.specs.iter().find(|spec| spec.name == name)?.handler
+    }
+}
+
+/// Splits corrupted memory into the instructions of a set, skipping everything else
+pub trait Tokenizer {
+    fn tokenize(&self, input: &str) -> Vec<Instruction>;
+}
+
+/// Interpreter state, open for custom instructions to change
+#[derive(Debug, Clone)]
+pub struct Machine {
+    pub enabled: bool,
+    pub total: u64,
+    /// Free for custom instructions, e.g. to save `enabled` for nested scopes
+    #[allow(dead_code)]
+    pub stack: Vec<bool>,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self { enabled: true, total: 0, stack: vec![] }
+    }
+}
+
+impl Machine {
+    pub fn execute(&mut self, set: &InstructionSet, instruction: &Instruction) {
+        match instruction {
+            Instruction::Mul(a, b) if self.enabled => self.total += *a as u64 * *b as u64,
+            Instruction::Mul(_, _) => {},
+            Instruction::Do => self.enabled = true,
+            Instruction::Dont => self.enabled = false,
+            Instruction::Custom(name, args) => {
+                if let Some(handler) = set.handler(name) {
+                    handler(self, args);
+                }
+            },
+        }
+    }
+
+    /// Total of the enabled multiplications
+    pub fn run<'a>(set: &InstructionSet, instructions: impl IntoIterator<Item = &'a Instruction>) -> u64 {
+        let mut machine = Self::default();
+        instructions.into_iter().for_each(|instruction| machine.execute(set, instruction));
+        machine.total
+    }
+}
@@ -0,0 +1,28 @@
+mod solution;
+#[path = "../../telemetry.rs"]
+mod telemetry;
+use solution::Day3;
+use solution::Solution;
+use solution::budget::{self, Budget};
+use solution::{instruction::InstructionSet, run_stream};
+use miette::{IntoDiagnostic, Result};
+use std::{fs::File, io::BufReader};
+
+fn main() -> Result<()> {
+    let _telemetry = telemetry::init();
+    budget::cancel_on_interrupt();
+    let budget = Budget::from_env()?;
+    // A memory dump too large to keep in memory can be given as the first argument
+    if let Some(path) = std::env::args().nth(1) {
+        let reader = BufReader::new(File::open(path).into_diagnostic()?);
+        println!("Part 2: {:?}", budget.run(|| run_stream(reader, &InstructionSet::part2())));
+        return Ok(());
+    }
+
+    let input = include_str!("../../../inputs/day3.txt");
+    let parsed = Day3::parse(input)?;
+    println!("Part 1: {:?}", budget.run(|| Day3::part1(&parsed)));
+    println!("Part 2: {:?}", budget.run(|| Day3::part2(&parsed)));
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use nom::{
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::anychar,
+    combinator::{map_res, verify},
+    multi::{many0, many_till, separated_list0},
+    sequence::{delimited, preceded},
+    IResult,
+};
+
+use super::instruction::{Instruction, InstructionSet, Spec, Tokenizer};
+
+pub struct NomTokenizer {
+    set: InstructionSet,
+}
+
+impl NomTokenizer {
+    pub fn new(set: &InstructionSet) -> Self {
+        Self { set: set.clone() }
+    }
+}
+
+fn argument(input: &str) -> IResult<&str, u32> {
+    map_res(take_while_m_n(1, 3, |c: char| c.is_ascii_digit()), str::parse)(input)
+}
+
+fn spec_instruction<'a>(spec: &Spec, input: &'a str) -> IResult<&'a str, Vec<u32>> {
+    verify(
+        preceded(
+            tag(spec.name),
+            delimited(tag("("), separated_list0(tag(","), argument), tag(")")),
+        ),
+        |args: &Vec<u32>| args.len() == spec.arity,
+    )(input)
+}
+
+impl NomTokenizer {
+    fn instruction<'a>(&self, input: &'a str) -> IResult<&'a str, Instruction> {
+        self.set.specs().iter()
+            .find_map(|spec| {
+                let (rest, args) = spec_instruction(spec, input).ok()?;
+                Some((rest, spec.instruction(args)?))
+            })
+            .ok_or(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Alt)))
+    }
+}
+
+impl Tokenizer for NomTokenizer {
+    fn tokenize(&self, input: &str) -> Vec<Instruction> {
+        many0(many_till(anychar, |i| self.instruction(i)))(input)
+            .unwrap()
+            .1
+            .into_iter()
+            .map(|(_, i)| i)
+            .collect()
+    }
+}
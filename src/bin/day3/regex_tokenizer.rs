@@ -0,0 +1,40 @@
+use itertools::Itertools;
+use regex::Regex;
+
+use super::instruction::{Instruction, InstructionSet, Tokenizer};
+
+pub struct RegexTokenizer {
+    set: InstructionSet,
+    regex: Regex,
+}
+
+impl RegexTokenizer {
+    pub fn new(set: &InstructionSet) -> Self {
+        // One named group per instruction so the match tells which one was found
+        let pattern = set.specs().iter().enumerate()
+            .map(|(i, spec)| format!(r"(?P<i{i}>{}\({}\))", regex::escape(spec.name), vec![r"\d{1,3}"; spec.arity].join(",")))
+            .join("|");
+
+        Self {
+            set: set.clone(),
+            regex: Regex::new(&pattern).unwrap(),
+        }
+    }
+}
+
+impl Tokenizer for RegexTokenizer {
+    fn tokenize(&self, input: &str) -> Vec<Instruction> {
+        self.regex.captures_iter(input)
+        .filter_map(|c| {
+            let (i, spec) = self.set.specs().iter().enumerate().find(|(i, _)| c.name(&format!("i{i}")).is_some())?;
+            let group = c.name(&format!("i{i}")).unwrap();
+            let args = group.as_str()[spec.name.len() + 1..group.len() - 1]
+                .split(',')
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| arg.parse::<u32>().unwrap())
+                .collect();
+            spec.instruction(args)
+        })
+        .collect()
+    }
+}
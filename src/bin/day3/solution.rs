@@ -0,0 +1,175 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "instruction.rs"]
+pub mod instruction;
+#[path = "regex_tokenizer.rs"]
+pub mod regex_tokenizer;
+// A second tokenizer backend for `run`, cross-checked against the regex one
+#[allow(dead_code)]
+#[path = "nom_tokenizer.rs"]
+pub mod nom_tokenizer;
+#[path = "stream.rs"]
+pub mod stream;
+
+pub use utils::Solution;
+use instruction::{Instruction, InstructionSet, Machine, Tokenizer};
+use miette::{IntoDiagnostic, Result};
+use regex_tokenizer::RegexTokenizer;
+use std::io::BufRead;
+use stream::StreamTokenizer;
+pub struct Day3;
+
+/// Run a memory dump with any instruction set and tokenizer backend
+#[allow(dead_code)]
+pub fn run(input: &str, set: &InstructionSet, tokenizer: &impl Tokenizer) -> u64 {
+    Machine::run(set, &tokenizer.tokenize(input))
+}
+
+/// Run a memory dump without holding all of it in memory
+pub fn run_stream(reader: impl BufRead, set: &InstructionSet) -> Result<u64> {
+    let mut machine = Machine::default();
+    for instruction in StreamTokenizer::new(set, reader) {
+        machine.execute(set, &instruction.into_diagnostic()?);
+    }
+
+    Ok(machine.total)
+}
+
+impl Solution<u64> for Day3 {
+    type Parsed = Vec<Instruction>;
+
+    /// Tokenized with the part 2 set, part 1 only has to skip the `do()` and `don't()`
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let set = InstructionSet::part2();
+        Ok(RegexTokenizer::new(&set).tokenize(input))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(instructions: &Self::Parsed) -> Result<u64> {
+        let multiplications = instructions.iter().filter(|instruction| matches!(instruction, Instruction::Mul(_, _)));
+        Ok(Machine::run(&InstructionSet::part1(), multiplications))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2(instructions: &Self::Parsed) -> Result<u64> {
+        Ok(Machine::run(&InstructionSet::part2(), instructions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{run_stream, Day3, Solution};
+    #[allow(unused_imports)]
+    use super::instruction::{Instruction, InstructionSet, Machine, Tokenizer};
+    #[allow(unused_imports)]
+    use super::nom_tokenizer::NomTokenizer;
+    #[allow(unused_imports)]
+    use super::regex_tokenizer::RegexTokenizer;
+    #[allow(unused_imports)]
+    use super::stream::StreamTokenizer;
+    #[allow(unused_imports)]
+    use std::io::{self, BufReader};
+
+    /// Deterministic mix of instruction fragments and noise
+    #[allow(dead_code)]
+    fn corrupted_memory(seed: u64, length: usize) -> String {
+        const PIECES: [&str; 16] = ["mul(", "do()", "don't()", "add(", ",", ")", "(", "1", "23", "456", "7890", "x", "mul", "don't", "[", " "];
+        let mut state = seed.max(1);
+        (0..length).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            PIECES[(state % PIECES.len() as u64) as usize]
+        }).collect()
+    }
+
+    #[allow(dead_code)]
+    fn differential(set: &InstructionSet) {
+        let regex = RegexTokenizer::new(set);
+        let nom = NomTokenizer::new(set);
+        (1..200).for_each(|seed| {
+            let input = corrupted_memory(seed, 300);
+            assert_eq!(regex.tokenize(&input), nom.tokenize(&input), "{input}");
+        });
+    }
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"#], super::fuzz::solve::<Day3, _>);
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        differential(&InstructionSet::part1());
+        differential(&InstructionSet::part2());
+        differential(&InstructionSet::part2().with("add", 2, |_, _| {}));
+        assert!(NomTokenizer::new(&InstructionSet::part2()).tokenize("no instructions").is_empty());
+    }
+
+    #[test]
+    fn test_stream_chunk_boundaries() {
+        let set = InstructionSet::part2().with("add", 2, |_, _| {});
+        let regex = RegexTokenizer::new(&set);
+        (1..50).for_each(|seed| {
+            let input = corrupted_memory(seed, 200);
+            (1..=8).for_each(|capacity| {
+                let reader = BufReader::with_capacity(capacity, input.as_bytes());
+                let streamed = StreamTokenizer::new(&set, reader).collect::<io::Result<Vec<_>>>().unwrap();
+                assert_eq!(streamed, regex.tokenize(&input), "{capacity} {input}");
+            });
+        });
+    }
+
+    #[test]
+    fn test_stream_large_dump() {
+        use std::io::Read;
+
+        let noise = || io::repeat(b'm').take(1_000_000);
+        let reader = BufReader::new(noise().chain(&b"mul(2,3)don't()mul(4,4)do()"[..]).chain(noise()).chain(&b"mul(5,5)"[..]));
+        assert_eq!(run_stream(reader, &InstructionSet::part2()).unwrap(), 31);
+    }
+
+    #[test]
+    fn test_custom_instructions() {
+        let set = InstructionSet::part2()
+            .with("add", 2, |machine, args| {
+                if machine.enabled {
+                    machine.total += (args[0] + args[1]) as u64;
+                }
+            })
+            .with("begin", 0, |machine, _| machine.stack.push(machine.enabled))
+            .with("end", 0, |machine, _| machine.enabled = machine.stack.pop().unwrap_or(true));
+        let input = "mul(2,3)add(1,1)begin()don't()mul(5,5)add(9,9)end()add(100,0)mul(1000,1)";
+        let instructions = RegexTokenizer::new(&set).tokenize(input);
+        assert_eq!(instructions[1], Instruction::Custom("add", vec![1, 1]));
+        assert_eq!(instructions, NomTokenizer::new(&set).tokenize(input));
+        // `mul(1000,1)` has a four digit argument, so it isn't an instruction
+        assert_eq!(Machine::run(&set, &instructions), 6 + 2 + 100);
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"#;
+        let result = Day3::part1(&Day3::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 161)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"#;
+        let result = Day3::part2(&Day3::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 48)
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+use super::instruction::{Instruction, InstructionSet, Spec};
+
+enum Scan {
+    Complete(Vec<u32>),
+    Prefix,
+    Fail,
+}
+
+/// Match the bytes against the start of an instruction
+fn scan(spec: &Spec, bytes: &[u8]) -> Scan {
+    let name = spec.name.as_bytes();
+    let length = bytes.len().min(name.len());
+    if bytes[..length] != name[..length] {
+        return Scan::Fail;
+    }
+    let Some((open, mut rest)) = bytes.get(name.len()..).and_then(|rest| rest.split_first()) else {
+        return Scan::Prefix;
+    };
+    if *open != b'(' {
+        return Scan::Fail;
+    }
+
+    let mut args = Vec::with_capacity(spec.arity);
+    for i in 0..spec.arity {
+        if i > 0 {
+            match rest.split_first() {
+                None => return Scan::Prefix,
+                Some((b',', after)) => rest = after,
+                Some(_) => return Scan::Fail,
+            }
+        }
+        let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+        if digits > 3 {
+            return Scan::Fail;
+        }
+        if digits == rest.len() {
+            return Scan::Prefix;
+        }
+        if digits == 0 {
+            return Scan::Fail;
+        }
+        args.push(rest[..digits].iter().fold(0, |acc, b| acc * 10 + (b - b'0') as u32));
+        rest = &rest[digits..];
+    }
+
+    match rest {
+        [] => Scan::Prefix,
+        [b')'] => Scan::Complete(args),
+        _ => Scan::Fail,
+    }
+}
+
+/// Scanner state that outlives a single chunk of input
+struct Scanner {
+    set: InstructionSet,
+    /// Bytes that are still the start of some instruction, at most one instruction long
+    pending: Vec<u8>,
+    ready: VecDeque<Instruction>,
+}
+
+impl Scanner {
+    fn feed(&mut self, byte: u8) {
+        self.pending.push(byte);
+
+        let mut prefix = false;
+        for spec in self.set.specs() {
+            match scan(spec, &self.pending) {
+                Scan::Complete(args) => {
+                    if let Some(instruction) = spec.instruction(args) {
+                        self.ready.push_back(instruction);
+                        self.pending.clear();
+                        return;
+                    }
+                },
+                Scan::Prefix => prefix = true,
+                Scan::Fail => {},
+            }
+        }
+        if prefix {
+            return;
+        }
+
+        // Nothing starts at the first pending byte, so look for instructions starting after it
+        let rest = self.pending.split_off(1);
+        self.pending.clear();
+        rest.into_iter().for_each(|byte| self.feed(byte));
+    }
+}
+
+/// Reads instructions from corrupted memory of any size, holding at most one chunk of the input
+pub struct StreamTokenizer<R> {
+    reader: R,
+    scanner: Scanner,
+    done: bool,
+}
+
+impl<R: BufRead> StreamTokenizer<R> {
+    pub fn new(set: &InstructionSet, reader: R) -> Self {
+        Self {
+            reader,
+            scanner: Scanner { set: set.clone(), pending: vec![], ready: VecDeque::new() },
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for StreamTokenizer<R> {
+    type Item = io::Result<Instruction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(instruction) = self.scanner.ready.pop_front() {
+                return Some(Ok(instruction));
+            }
+            if self.done {
+                return None;
+            }
+
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                },
+            };
+            if chunk.is_empty() {
+                self.done = true;
+                continue;
+            }
+            chunk.iter().for_each(|byte| self.scanner.feed(*byte));
+            let length = chunk.len();
+            self.reader.consume(length);
+        }
+    }
+}
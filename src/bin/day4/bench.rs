@@ -0,0 +1,26 @@
+mod solution;
+use solution::Day4;
+use solution::Solution;
+
+const INPUT: &str = include_str!("../../../inputs/day4.txt");
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day4::parse(divan::black_box(INPUT)));
+}
+
+#[divan::bench]
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day4::parse(INPUT).unwrap();
+    bencher.bench(|| Day4::part1(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day4::parse(INPUT).unwrap();
+    bencher.bench(|| Day4::part2(divan::black_box(&parsed)));
+}
@@ -0,0 +1,19 @@
+mod solution;
+#[path = "../../telemetry.rs"]
+mod telemetry;
+use solution::Day4;
+use solution::Solution;
+use solution::budget::{self, Budget};
+use miette::Result;
+
+fn main() -> Result<()> {
+    let _telemetry = telemetry::init();
+    budget::cancel_on_interrupt();
+    let budget = Budget::from_env()?;
+    let input = include_str!("../../../inputs/day4.txt");
+    let parsed = Day4::parse(input)?;
+    println!("Part 1: {:?}", budget.run(|| Day4::part1(&parsed)));
+    println!("Part 2: {:?}", budget.run(|| Day4::part2(&parsed)));
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+use glam::IVec2;
+
+/// Unit steps in 45° increments, clockwise from east (y points down)
+const DIRECTIONS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+];
+
+/// Placement of a pattern on the grid: mirrored top to bottom if `reflected`, then
+/// rotated clockwise by `rotation` steps of 45°
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub rotation: u8,
+    pub reflected: bool,
+}
+
+impl Orientation {
+    /// Every rotation in 45° steps, with and without reflection, the diagonal ones
+    /// stretch two dimensional patterns into diamonds
+    pub fn all() -> Vec<Self> {
+        (0..8).flat_map(|rotation| [false, true].map(|reflected| Self { rotation, reflected })).collect()
+    }
+
+    /// Rotations by right angles, with and without reflection
+    pub fn right_angles() -> Vec<Self> {
+        Self::all().into_iter().filter(|o| o.rotation % 2 == 0).collect()
+    }
+
+    /// Direction the pattern's first row reads in
+    pub fn direction(&self) -> IVec2 {
+        DIRECTIONS[self.rotation as usize]
+    }
+
+    fn apply(&self, offset: IVec2) -> IVec2 {
+        let y = if self.reflected { -offset.y } else { offset.y };
+        offset.x * self.direction() + y * DIRECTIONS[(self.rotation as usize + 2) % 8]
+    }
+}
+
+/// Characters at offsets from the pattern origin, cells that aren't listed match anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    cells: Vec<(IVec2, char)>,
+}
+
+impl Pattern {
+    pub fn word(word: &str) -> Self {
+        Self {
+            cells: word.chars().enumerate().map(|(i, c)| (IVec2::new(i as i32, 0), c)).collect(),
+        }
+    }
+
+    /// One pattern per word, for searching a whole word list at once
+    pub fn words(words: &[&str]) -> Vec<Self> {
+        words.iter().map(|word| Self::word(word)).collect()
+    }
+
+    /// Rows of characters where `wildcard` matches anything, the origin is the top left corner
+    pub fn stencil(text: &str, wildcard: char) -> Self {
+        Self {
+            cells: text.lines().enumerate()
+                .flat_map(|(y, line)| line.chars().enumerate().map(move |(x, c)| (IVec2::new(x as i32, y as i32), c)))
+                .filter(|(_, c)| *c != wildcard)
+                .collect(),
+        }
+    }
+
+    fn oriented(&self, orientation: &Orientation) -> Vec<(IVec2, char)> {
+        self.cells.iter().map(|(offset, c)| (orientation.apply(*offset), *c)).collect()
+    }
+
+    /// Drop orientations that put the same characters on the same cells as an earlier one,
+    /// so symmetric patterns aren't counted more than once per placement
+    fn distinct(&self, orientations: &[Orientation]) -> Vec<(Orientation, Vec<(IVec2, char)>)> {
+        let mut seen = HashSet::new();
+        orientations.iter()
+            .map(|orientation| (*orientation, self.oriented(orientation)))
+            .filter(|(_, cells)| {
+                let min = cells.iter().fold(IVec2::MAX, |min, (offset, _)| min.min(*offset));
+                let mut shape = cells.iter().map(|(offset, c)| ((*offset - min).to_array(), *c)).collect::<Vec<_>>();
+                shape.sort();
+                seen.insert(shape)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the pattern in the searched list
+    pub pattern: usize,
+    /// Grid position of the pattern origin
+    pub position: IVec2,
+    pub orientation: Orientation,
+}
+
+/// Every placement of each pattern, overlapping ones included
+pub fn find(grid: &[Vec<char>], patterns: &[Pattern], orientations: &[Orientation]) -> Vec<Match> {
+    let at = |position: IVec2| -> Option<char> {
+        grid.get(usize::try_from(position.y).ok()?)?.get(usize::try_from(position.x).ok()?).copied()
+    };
+
+    patterns.iter().enumerate()
+        .flat_map(|(index, pattern)| pattern.distinct(orientations).into_iter().map(move |(orientation, cells)| (index, orientation, cells)))
+        .flat_map(|(index, orientation, cells)| {
+            grid.iter().enumerate()
+                .flat_map(|(y, row)| (0..row.len()).map(move |x| IVec2::new(x as i32, y as i32)))
+                .filter(move |position| cells.iter().all(|(offset, c)| at(*position + *offset) == Some(*c)))
+                .map(move |position| Match { pattern: index, position, orientation })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use glam::IVec2;
+    #[allow(unused_imports)]
+    use super::{find, Match, Orientation, Pattern};
+
+    #[allow(dead_code)]
+    fn grid(text: &str) -> Vec<Vec<char>> {
+        text.lines().map(|l| l.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_word_orientations() {
+        let grid = grid("XMAS\n.M..\n..A.\n...S");
+        let matches = find(&grid, &[Pattern::word("XMAS")], &Orientation::all());
+        let directions = matches.iter().map(|m| (m.position, m.orientation.direction())).collect::<Vec<_>>();
+        assert_eq!(directions, vec![
+            (IVec2::new(0, 0), IVec2::new(1, 0)),
+            (IVec2::new(0, 0), IVec2::new(1, 1)),
+        ]);
+        assert_eq!(matches[0], Match { pattern: 0, position: IVec2::ZERO, orientation: Orientation { rotation: 0, reflected: false } });
+    }
+
+    #[test]
+    fn test_symmetric_and_overlapping() {
+        // A palindrome reads the same both ways, so each placement counts once
+        assert_eq!(find(&grid("AAAA"), &[Pattern::word("AA")], &Orientation::all()).len(), 3);
+        assert_eq!(find(&grid("AAAA"), &[Pattern::word("ABA")], &Orientation::all()).len(), 0);
+        assert_eq!(find(&grid(".ABAB."), &[Pattern::word("ABA")], &Orientation::all()).len(), 1);
+        assert_eq!(find(&grid("ABABA"), &[Pattern::word("ABA")], &Orientation::all()).len(), 2);
+    }
+
+    #[test]
+    fn test_stencil() {
+        let grid = grid("S.S\n.A.\nM.M\n.A.\nS.S");
+        let cross = [Pattern::stencil("M.S\n.A.\nM.S", '.')];
+        let matches = find(&grid, &cross, &Orientation::right_angles());
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|m| m.orientation.rotation % 2 == 0));
+        assert!(find(&grid, &cross, &[Orientation { rotation: 0, reflected: false }]).is_empty());
+    }
+
+    #[test]
+    fn test_word_list() {
+        let grid = grid("XMAS\nSAMX\n.MA.");
+        let matches = find(&grid, &Pattern::words(&["XMAS", "MA", "SAX"]), &[Orientation { rotation: 0, reflected: false }]);
+        let found = matches.iter().map(|m| (m.pattern, m.position)).collect::<Vec<_>>();
+        assert_eq!(found, vec![
+            (0, IVec2::new(0, 0)),
+            (1, IVec2::new(1, 0)),
+            (1, IVec2::new(1, 2)),
+        ]);
+        assert_eq!(find(&grid, &Pattern::words(&[]), &Orientation::all()), vec![]);
+    }
+}
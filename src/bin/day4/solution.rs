@@ -0,0 +1,95 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "search.rs"]
+pub mod search;
+
+pub use utils::Solution;
+use miette::Result;
+use search::{find, Orientation, Pattern};
+pub struct Day4;
+
+pub fn parse(input: &str) -> Vec<Vec<char>> {
+    input.lines().map(|l| l.chars().collect()).collect()
+}
+
+impl Solution<u32> for Day4 {
+    type Parsed = Vec<Vec<char>>;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(parse(input))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(grid: &Self::Parsed) -> Result<u32> {
+        Ok(find(grid, &Pattern::words(&["XMAS"]), &Orientation::all()).len() as u32)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2(grid: &Self::Parsed) -> Result<u32> {
+        let cross = Pattern::stencil("M.S\n.A.\nM.S", '.');
+        Ok(find(grid, &[cross], &Orientation::right_angles()).len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Day4, Solution};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX"#], super::fuzz::solve::<Day4, _>);
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX"#;
+        let result = Day4::part1(&Day4::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 18)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX"#;
+        let result = Day4::part2(&Day4::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 9)
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{BTreeMap, BTreeSet};
+use itertools::Itertools;
+use miette::{miette, LabeledSpan, Result};
+
+/// Page ordering rule `before|after`
+pub type Rule = (u32, u32);
+
+/// Rule broken by an update, `after` is printed before `before`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    pub rule: Rule,
+    /// Positions of `before` and `after` in the update
+    pub indices: (usize, usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: BTreeSet<Rule>,
+}
+
+impl RuleSet {
+    pub fn new(rules: impl IntoIterator<Item = Rule>) -> Self {
+        Self { rules: rules.into_iter().collect() }
+    }
+
+    /// Every rule broken by the update, in the order the pages appear
+    pub fn violations(&self, update: &[u32]) -> Vec<Violation> {
+        update.iter().enumerate()
+            .tuple_combinations()
+            .filter(|((_, a), (_, b))| self.rules.contains(&(**b, **a)))
+            .map(|((i, a), (j, b))| Violation { rule: (*b, *a), indices: (j, i) })
+            .collect()
+    }
+
+    /// Graph of the rules between the pages of an update
+    pub fn graph<'a>(&self, update: &'a [u32]) -> PageGraph<'a> {
+        let mut edges: BTreeMap<u32, BTreeSet<u32>> = update.iter().map(|page| (*page, BTreeSet::new())).collect();
+        update.iter().tuple_combinations().for_each(|(a, b)| {
+            if self.rules.contains(&(*a, *b)) {
+                edges.entry(*a).or_default().insert(*b);
+            }
+            if self.rules.contains(&(*b, *a)) {
+                edges.entry(*b).or_default().insert(*a);
+            }
+        });
+
+        PageGraph { update, edges }
+    }
+}
+
+/// Pages of one update with an edge for every rule that applies to them
+#[derive(Debug, Clone)]
+pub struct PageGraph<'a> {
+    update: &'a [u32],
+    edges: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl PageGraph<'_> {
+    /// The only ordering of the pages that follows every rule, fails when the
+    /// rules form a cycle or leave two pages unordered
+    pub fn order(&self) -> Result<Vec<u32>> {
+        let mut incoming: BTreeMap<u32, usize> = self.edges.keys().map(|page| (*page, 0)).collect();
+        self.edges.values().flatten().for_each(|page| *incoming.get_mut(page).unwrap() += 1);
+
+        let mut ready = incoming.iter().filter(|(_, count)| **count == 0).map(|(page, _)| *page).collect::<BTreeSet<_>>();
+        let mut order = Vec::with_capacity(self.edges.len());
+        while let Some(page) = ready.pop_first() {
+            // Two pages are ready at once only if no chain of rules connects them
+            if let Some(other) = ready.first() {
+                return Err(self.ambiguous(page, *other));
+            }
+            order.push(page);
+            self.edges[&page].iter().for_each(|next| {
+                let count = incoming.get_mut(next).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(*next);
+                }
+            });
+        }
+
+        if order.len() < self.edges.len() {
+            let remaining = incoming.into_iter().filter(|(_, count)| *count > 0).map(|(page, _)| page).collect();
+            return Err(self.cycle(&remaining));
+        }
+
+        Ok(order)
+    }
+
+    /// Walk backwards through pages that still have incoming edges until one repeats
+    fn find_cycle(&self, remaining: &BTreeSet<u32>) -> Vec<u32> {
+        let mut path = vec![*remaining.first().unwrap()];
+        loop {
+            let current = *path.last().unwrap();
+            let previous = *remaining.iter()
+                .find(|page| self.edges[page].contains(&current))
+                .unwrap();
+            if let Some(start) = path.iter().position(|page| *page == previous) {
+                let mut cycle = path.split_off(start);
+                cycle.reverse();
+                let smallest = cycle.iter().position_min().unwrap();
+                cycle.rotate_left(smallest);
+                return cycle;
+            }
+            path.push(previous);
+        }
+    }
+
+    /// The update as source text and the span of every page in it
+    fn source(&self) -> (String, BTreeMap<u32, (usize, usize)>) {
+        let mut spans = BTreeMap::new();
+        let mut offset = 0;
+        let text = self.update.iter().map(|page| {
+            let text = page.to_string();
+            spans.insert(*page, (offset, text.len()));
+            offset += text.len() + 1;
+            text
+        }).join(",");
+
+        (text, spans)
+    }
+
+    fn cycle(&self, remaining: &BTreeSet<u32>) -> miette::Report {
+        let cycle = self.find_cycle(remaining);
+        let (source, spans) = self.source();
+        let rules = cycle.iter().circular_tuple_windows().map(|(a, b)| format!("{a}|{b}")).join(", ");
+        let labels = cycle.iter().circular_tuple_windows()
+            .map(|(a, b)| LabeledSpan::at(spans[a].0..spans[a].0 + spans[a].1, format!("must come before {b}")))
+            .collect::<Vec<_>>();
+
+        miette!(
+            code = "day5::cycle",
+            labels = labels,
+            help = "remove one of the rules to break the cycle",
+            "Rules {rules} form a cycle",
+        ).with_source_code(source)
+    }
+
+    fn ambiguous(&self, a: u32, b: u32) -> miette::Report {
+        let (source, spans) = self.source();
+        let labels = [a, b].iter()
+            .map(|page| LabeledSpan::at(spans[page].0..spans[page].0 + spans[page].1, "unordered"))
+            .collect::<Vec<_>>();
+
+        miette!(
+            code = "day5::ambiguous",
+            labels = labels,
+            help = format!("add the rule {a}|{b} or {b}|{a}"),
+            "No rule decides the order of pages {a} and {b}",
+        ).with_source_code(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{RuleSet, Violation};
+
+    #[allow(dead_code)]
+    fn rules() -> RuleSet {
+        RuleSet::new([(47, 53), (97, 13), (97, 61), (97, 47), (75, 29), (61, 13), (75, 53), (29, 13), (97, 29), (53, 29), (61, 53), (97, 53), (61, 29), (47, 13), (75, 47), (97, 75), (47, 61), (75, 61), (47, 29), (75, 13), (53, 13)])
+    }
+
+    #[test]
+    fn test_violations() {
+        let rules = rules();
+        assert!(rules.violations(&[75, 47, 61, 53, 29]).is_empty());
+        assert_eq!(rules.violations(&[75, 97, 47, 61, 53]), vec![
+            Violation { rule: (97, 75), indices: (1, 0) },
+        ]);
+        assert_eq!(rules.violations(&[61, 13, 29]), vec![
+            Violation { rule: (29, 13), indices: (2, 1) },
+        ]);
+    }
+
+    #[test]
+    fn test_order() {
+        let rules = rules();
+        assert_eq!(rules.graph(&[75, 97, 47, 61, 53]).order().unwrap(), vec![97, 75, 47, 61, 53]);
+        assert_eq!(rules.graph(&[97, 13, 75, 29, 47]).order().unwrap(), vec![97, 75, 47, 29, 13]);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let rules = RuleSet::new([(1, 2), (2, 3), (3, 1), (4, 1)]);
+        let error = rules.graph(&[4, 3, 2, 1]).order().unwrap_err();
+        assert_eq!(error.to_string(), "Rules 1|2, 2|3, 3|1 form a cycle");
+        assert_eq!(error.labels().unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let rules = RuleSet::new([(1, 2), (1, 3)]);
+        let error = rules.graph(&[3, 2, 1]).order().unwrap_err();
+        assert_eq!(error.to_string(), "No rule decides the order of pages 2 and 3");
+        assert_eq!(error.help().unwrap().to_string(), "add the rule 2|3 or 3|2");
+    }
+}
@@ -0,0 +1,178 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "rules.rs"]
+pub mod rules;
+
+pub use utils::Solution;
+use miette::{miette, IntoDiagnostic, Result};
+use rules::RuleSet;
+pub struct Day5;
+
+pub fn parse(input: &str) -> Result<(RuleSet, Vec<Vec<u32>>)> {
+    let (rules, updates) = input.split_once("\n\n").ok_or(miette!("Missing blank line between rules and updates"))?;
+    let rules = rules.lines()
+    .map(|line| {
+        let (before, after) = line.split_once("|").ok_or(miette!("Rule {line:?} has no |"))?;
+        Ok((before.parse::<u32>().into_diagnostic()?, after.parse::<u32>().into_diagnostic()?))
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    let updates = updates.lines()
+    .map(|line| line.split(",").map(|i| i.parse::<u32>().into_diagnostic()).collect())
+    .collect::<Result<_>>()?;
+
+    Ok((RuleSet::new(rules), updates))
+}
+
+impl Solution<u32> for Day5 {
+    type Parsed = (RuleSet, Vec<Vec<u32>>);
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse(input)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1((rules, updates): &Self::Parsed) -> Result<u32> {
+
+        updates.iter()
+        .filter(|pages| rules.violations(pages).is_empty())
+        .try_fold(0u32, |sum, line| sum.checked_add(line[line.len() / 2]))
+        .ok_or(miette!("The sum of the middle pages doesn't fit in 32 bits"))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2((rules, updates): &Self::Parsed) -> Result<u32> {
+
+        updates.iter()
+        .filter(|pages| !rules.violations(pages).is_empty())
+        .try_fold(0u32, |sum, pages| {
+            let order = rules.graph(pages).order()?;
+
+            sum.checked_add(order[order.len() / 2]).ok_or(miette!("The sum of the middle pages doesn't fit in 32 bits"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Day5, Solution};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47"#], super::fuzz::solve::<Day5, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day5::part1(&Day5::parse("1|2\n\n4000000000\n4000000000").unwrap()).is_err());
+        let updates = Day5::parse("1|3000000000\n3000000000|2\n\n2,3000000000,1\n2,3000000000,1").unwrap();
+        assert!(Day5::part2(&updates).is_err());
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47"#;
+        let result = Day5::part1(&Day5::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 143)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47"#;
+        let result = Day5::part2(&Day5::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 123)
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{HashMap, HashSet};
+use rayon::prelude::*;
+
+use super::{budget::{Budget, Exhausted}, AocMap, Direction, State};
+
+const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+/// An obstacle position and the turning points of the loop it traps the guard in
+pub type Trap = ((isize, isize), Vec<State>);
+
+/// Patrol engine that jumps straight from obstacle to obstacle
+#[derive(Debug)]
+pub struct Patrol {
+    width: isize,
+    /// For every direction and cell, the last free cell before the next obstacle in that
+    /// row or column, or None if the guard would walk off the map
+    jumps: [Vec<Option<(isize, isize)>>; 4],
+}
+
+impl Patrol {
+    pub fn new(map: &AocMap) -> Self {
+        let mut jumps: [Vec<Option<(isize, isize)>>; 4] = Default::default();
+
+        DIRECTIONS.iter().for_each(|direction| {
+            let (dx, dy) = direction.to_tuple();
+            let mut table = vec![None; (map.width * map.height) as usize];
+
+            // Walk every row or column against the direction of travel so that the closest
+            // obstacle ahead is always known
+            let lines = if dx != 0 { map.height } else { map.width };
+            let length = if dx != 0 { map.width } else { map.height };
+            (0..lines).for_each(|line| {
+                let mut stop = None;
+                (0..length).for_each(|i| {
+                    let i = if dx + dy > 0 { length - 1 - i } else { i };
+                    let position = if dx != 0 { (i, line) } else { (line, i) };
+                    if map.obstacles.contains(&position) {
+                        stop = Some((position.0 - dx, position.1 - dy));
+                    } else {
+                        table[(position.1 * map.width + position.0) as usize] = stop;
+                    }
+                });
+            });
+
+            jumps[*direction as usize] = table;
+        });
+
+        Self {
+            width: map.width,
+            jumps,
+        }
+    }
+
+    /// Where the guard stops when walking from `position` towards `direction`
+    fn next_stop(&self, position: (isize, isize), direction: Direction, extra_obstacle: Option<(isize, isize)>) -> Option<(isize, isize)> {
+        let stop = self.jumps[direction as usize][(position.1 * self.width + position.0) as usize];
+        let Some(obstacle) = extra_obstacle else {
+            return stop;
+        };
+
+        let (dx, dy) = direction.to_tuple();
+        let ahead = (obstacle.0 - position.0) * dx + (obstacle.1 - position.1) * dy;
+        let on_line = if dx != 0 { obstacle.1 == position.1 } else { obstacle.0 == position.0 };
+        if !on_line || ahead <= 0 {
+            return stop;
+        }
+
+        let before_stop = stop.is_none_or(|(x, y)| ahead <= (x - position.0) * dx + (y - position.1) * dy);
+        if before_stop {
+            Some((obstacle.0 - dx, obstacle.1 - dy))
+        } else {
+            stop
+        }
+    }
+
+    /// Patrol from the given state, returns the turning points forming the loop if the guard never leaves,
+    /// every jump is a step of the budget
+    pub fn find_loop(&self, position: (isize, isize), direction: Direction, extra_obstacle: Option<(isize, isize)>, budget: &Budget) -> Result<Option<Vec<State>>, Exhausted> {
+        let mut position = position;
+        let mut direction = direction;
+        let mut turns: Vec<State> = vec![];
+        let mut seen = HashMap::new();
+
+        loop {
+            budget.step()?;
+            let Some(stop) = self.next_stop(position, direction, extra_obstacle) else {
+                return Ok(None);
+            };
+            position = stop;
+
+            if let Some(start) = seen.insert((position, direction), turns.len()) {
+                return Ok(Some(turns.split_off(start)));
+            }
+            turns.push((position, direction));
+            direction = direction.turn();
+        }
+    }
+
+    /// Every position on the original route that traps the guard in a loop when blocked,
+    /// together with the loop it causes
+    pub fn loop_obstacles(&self, route: &[State], start: (isize, isize), budget: &Budget) -> Result<Vec<Trap>, Exhausted> {
+        let mut tried = HashSet::from([start]);
+
+        let found = route.iter()
+            .filter(|(position, _)| tried.insert(*position))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(position, direction)| {
+                // Resume from the step just before the guard first reaches the new obstacle
+                let (dx, dy) = direction.to_tuple();
+                let previous = (position.0 - dx, position.1 - dy);
+                Ok(self.find_loop(previous, *direction, Some(*position), budget)?.map(|cycle| (*position, cycle)))
+            })
+            .collect::<Result<Vec<_>, Exhausted>>()?;
+
+        Ok(found.into_iter().flatten().collect())
+    }
+}
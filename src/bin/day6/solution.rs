@@ -0,0 +1,252 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "patrol.rs"]
+mod patrol;
+
+pub use utils::Solution;
+use std::collections::HashSet;
+use miette::{miette, Result};
+use patrol::Patrol;
+pub struct Day6;
+
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Copy, Hash)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn turn(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    fn to_tuple(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+}
+
+/// Guard position and the direction it is facing
+type State = ((isize, isize), Direction);
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    Finished(Vec<State>),
+    /// The points where the guard turns while going around the loop
+    Loop(Vec<State>),
+}
+
+#[derive(Debug, Clone)]
+pub struct AocMap {
+    obstacles: HashSet<(isize, isize)>,
+    guard_position: (isize, isize),
+    width: isize,
+    height: isize,
+}
+
+fn get_guard_route(map: &AocMap, extra_obsticle: Option<&(isize, isize)>) -> Route {
+    let mut guard_position = map.guard_position;
+    let mut guard_direction = Direction::Up;
+    let mut visited = vec![];
+    let mut turns: Vec<State> = vec![];
+
+    loop {
+        let (dx, dy) = guard_direction.to_tuple();
+        let new_position = (guard_position.0 + dx, guard_position.1 + dy);
+        if new_position.0 < 0 || new_position.0 >= map.width || new_position.1 < 0 || new_position.1 >= map.height {
+            break;
+        }
+
+        if map.obstacles.contains(&new_position) || extra_obsticle == Some(&new_position) {
+            // Every loop has to turn somewhere, so it's enough to remember the turning points
+            if let Some(start) = turns.iter().position(|t| *t == (guard_position, guard_direction)) {
+                return Route::Loop(turns.split_off(start));
+            }
+            turns.push((guard_position, guard_direction));
+            guard_direction = guard_direction.turn();
+            continue;
+        }
+
+        guard_position = new_position;
+        visited.push((guard_position, guard_direction));
+    }
+
+    Route::Finished(visited)
+}
+
+fn parse_map(input: &str) -> Result<AocMap> {
+    let mut guard_position = None;
+    let mut obstacles = HashSet::new();
+
+    input
+    .lines().enumerate()
+    .for_each(|(y, line)| line.chars().enumerate().for_each(|(x, cell)| {
+        match cell {
+            '^' => guard_position = Some((x as isize, y as isize)),
+            '#' => { obstacles.insert((x as isize, y as isize)); },
+            _ => {},
+        }
+    }));
+
+    Ok(AocMap {
+        obstacles,
+        guard_position: guard_position.ok_or(miette!("The map has no guard"))?,
+        width: input.lines().next().map_or(0, str::len) as isize,
+        height: input.lines().count() as isize,
+    })
+}
+
+impl Solution<u32> for Day6 {
+    type Parsed = AocMap;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_map(input)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(map: &Self::Parsed) -> Result<u32> {
+        Ok(match get_guard_route(map, None) {
+            Route::Finished(route) => route.into_iter().map(|(position, _)| position).collect::<HashSet<_>>().len() as u32,
+            _ => 0,
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2(map: &Self::Parsed) -> Result<u32> {
+        Ok(match get_guard_route(map, None) {
+            Route::Finished(route) => Patrol::new(map).loop_obstacles(&route, map.guard_position, &budget::current())?.len() as u32,
+            _ => 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{budget::Budget, get_guard_route, parse_map, patrol::Patrol, Day6, Direction, Route, Solution};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."#], super::fuzz::solve::<Day6, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day6::parse("").is_err());
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."#;
+        let result = Day6::part1(&Day6::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 41)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."#;
+        let result = Day6::part2(&Day6::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 6)
+    }
+
+    #[test]
+    fn test_loop() {
+        let test = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......##.."#;
+        let result = get_guard_route(&parse_map(test).unwrap(), None);
+        assert_eq!(result, Route::Loop(vec![
+            ((1, 8), Direction::Left),
+            ((1, 7), Direction::Up),
+            ((7, 7), Direction::Right),
+            ((7, 8), Direction::Down),
+        ]))
+    }
+
+    #[test]
+    fn test_loop_obstacles() {
+        let test = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."#;
+        let map = parse_map(test).unwrap();
+        let Route::Finished(route) = get_guard_route(&map, None) else {
+            panic!("guard should leave the map");
+        };
+        let mut obstacles = Patrol::new(&map).loop_obstacles(&route, map.guard_position, &Budget::unlimited()).unwrap();
+        obstacles.sort();
+
+        assert_eq!(obstacles.iter().map(|(o, _)| *o).collect::<Vec<_>>(), vec![(1, 8), (3, 6), (3, 8), (6, 7), (7, 7), (7, 9)]);
+        obstacles.into_iter().for_each(|(obstacle, mut cycle)| {
+            // Both walkers may enter the loop at a different turn
+            let Route::Loop(mut expected) = get_guard_route(&map, Some(&obstacle)) else {
+                panic!("{:?} should cause a loop", obstacle);
+            };
+            expected.sort();
+            cycle.sort();
+            assert_eq!(cycle, expected);
+        });
+    }
+}
@@ -0,0 +1,236 @@
+/// Binary operator used between the numbers of a calibration equation
+pub trait Operator: Send + Sync {
+    fn symbol(&self) -> &str;
+
+    /// `left <op> right`, None on overflow
+    fn apply(&self, left: u64, right: u64) -> Option<u64>;
+
+    /// The left operands that give `result` with `right`
+    fn invert(&self, result: u64, right: u64) -> Candidates;
+}
+
+/// Left operands an operator can be inverted to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Candidates {
+    Nothing,
+    Exactly(u64),
+    /// Every left operand works, like with `0 * 0`
+    Any,
+}
+
+impl From<Option<u64>> for Candidates {
+    fn from(left: Option<u64>) -> Self {
+        left.map_or(Candidates::Nothing, Candidates::Exactly)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Operation {
+    Add,
+    Multiply,
+    Concat,
+}
+
+pub trait Concat: Sized {
+    fn checked_concat(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_concat {
+    ($($t:ty),*) => {
+        $(impl Concat for $t {
+            fn checked_concat(self, other: Self) -> Option<Self> {
+                let digits = other.checked_ilog10().unwrap_or(0) + 1;
+                self.checked_mul((10 as $t).checked_pow(digits)?)?.checked_add(other)
+            }
+        })*
+    };
+}
+
+impl_concat!(u8, u16, u32, u64, u128, usize);
+
+impl Operator for Operation {
+    fn symbol(&self) -> &str {
+        match self {
+            Operation::Add => "+",
+            Operation::Multiply => "*",
+            Operation::Concat => "||",
+        }
+    }
+
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        match self {
+            Operation::Add => left.checked_add(right),
+            Operation::Multiply => left.checked_mul(right),
+            Operation::Concat => left.checked_concat(right),
+        }
+    }
+
+    fn invert(&self, result: u64, right: u64) -> Candidates {
+        match self {
+            Operation::Add => result.checked_sub(right).into(),
+            Operation::Multiply if right == 0 && result == 0 => Candidates::Any,
+            Operation::Multiply if right == 0 || !result.is_multiple_of(right) => Candidates::Nothing,
+            Operation::Multiply => Candidates::Exactly(result / right),
+            Operation::Concat => 10u64.checked_pow(right.checked_ilog10().unwrap_or(0) + 1)
+                .filter(|pow| result % pow == right)
+                .map(|pow| result / pow)
+                .into(),
+        }
+    }
+}
+
+/// Searches operator sequences backwards from the target value
+#[derive(Default)]
+pub struct Solver {
+    operators: Vec<Box<dyn Operator>>,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(mut self, operator: impl Operator + 'static) -> Self {
+        self.operators.push(Box::new(operator));
+        self
+    }
+
+    /// Operators to put between `numbers` (evaluated left to right) to get `target`
+    pub fn solve(&self, target: u64, numbers: &[u64]) -> Option<Vec<&dyn Operator>> {
+        let mut operators = Vec::with_capacity(numbers.len().saturating_sub(1));
+        self.search(target, numbers, &mut operators).then(|| {
+            operators.reverse();
+            operators
+        })
+    }
+
+    fn search<'a>(&'a self, target: u64, numbers: &[u64], operators: &mut Vec<&'a dyn Operator>) -> bool {
+        match numbers {
+            [] => false,
+            [first] => *first == target,
+            [rest @ .., last] => self.operators.iter().any(|operator| {
+                let length = operators.len();
+                operators.push(operator.as_ref());
+                let found = match operator.invert(target, *last) {
+                    Candidates::Nothing => false,
+                    Candidates::Exactly(left) => self.search(left, rest, operators),
+                    // Whatever `rest` evaluates to works, as long as it evaluates at all
+                    Candidates::Any => match rest.split_first() {
+                        Some((first, rest)) => {
+                            let found = self.evaluable(*first, rest, operators);
+                            // `search` collects operators last to first
+                            operators[length + 1..].reverse();
+                            found
+                        },
+                        None => false,
+                    },
+                };
+                if !found {
+                    operators.truncate(length);
+                }
+                found
+            }),
+        }
+    }
+
+    /// Operators that evaluate `numbers` after `value` without overflowing, first to last
+    fn evaluable<'a>(&'a self, value: u64, numbers: &[u64], operators: &mut Vec<&'a dyn Operator>) -> bool {
+        let Some((next, rest)) = numbers.split_first() else {
+            return true;
+        };
+        self.operators.iter().any(|operator| {
+            let Some(value) = operator.apply(value, *next) else {
+                return false;
+            };
+            operators.push(operator.as_ref());
+            if self.evaluable(value, rest, operators) {
+                return true;
+            }
+            operators.pop();
+            false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Candidates, Concat, Operation, Operator, Solver};
+
+    /// Evaluate `numbers` left to right with the given operators, None on overflow
+    #[allow(dead_code)]
+    fn evaluate(numbers: &[u64], operators: &[&dyn Operator]) -> Option<u64> {
+        let (first, rest) = numbers.split_first()?;
+        rest.iter().zip(operators).try_fold(*first, |acc, (number, operator)| operator.apply(acc, *number))
+    }
+
+    #[allow(dead_code)]
+    struct Subtract;
+
+    impl Operator for Subtract {
+        fn symbol(&self) -> &str {
+            "-"
+        }
+
+        fn apply(&self, left: u64, right: u64) -> Option<u64> {
+            left.checked_sub(right)
+        }
+
+        fn invert(&self, result: u64, right: u64) -> Candidates {
+            result.checked_add(right).into()
+        }
+    }
+
+    #[test]
+    fn test_concat() {
+        assert_eq!(12_u64.checked_concat(345), Some(12345));
+        assert_eq!(1_u64.checked_concat(345), Some(1345));
+        assert_eq!(123_u64.checked_concat(45), Some(12345));
+        assert_eq!(26_u8.checked_concat(5), None);
+        assert_eq!(u64::MAX.checked_concat(1), None);
+    }
+
+    #[test]
+    fn test_solve_sequence() {
+        let solver = Solver::new()
+            .register(Operation::Add)
+            .register(Operation::Multiply)
+            .register(Operation::Concat);
+
+        let numbers = [6, 8, 6, 15];
+        let operators = solver.solve(7290, &numbers).unwrap();
+        assert_eq!(operators.iter().map(|o| o.symbol()).collect::<Vec<_>>(), vec!["*", "||", "*"]);
+        assert_eq!(evaluate(&numbers, &operators), Some(7290));
+        assert!(solver.solve(21037, &[9, 7, 18, 13]).is_none());
+    }
+
+    #[test]
+    fn test_multiply_by_zero() {
+        let solver = Solver::new().register(Operation::Add).register(Operation::Multiply);
+        assert_eq!(Operation::Multiply.invert(0, 0), Candidates::Any);
+        assert_eq!(Operation::Multiply.invert(7, 0), Candidates::Nothing);
+
+        let operators = solver.solve(0, &[5, 0]).unwrap();
+        assert_eq!(operators.iter().map(|o| o.symbol()).collect::<Vec<_>>(), vec!["*"]);
+        let numbers = [3, 4, 0, 2];
+        let operators = solver.solve(2, &numbers).unwrap();
+        assert_eq!(evaluate(&numbers, &operators), Some(2));
+        assert!(solver.solve(1, &[5, 0]).is_none());
+        assert!(solver.solve(0, &[u64::MAX, u64::MAX, 0]).is_none());
+    }
+
+    #[test]
+    fn test_custom_operator() {
+        let solver = Solver::new().register(Operation::Add).register(Subtract);
+        let operators = solver.solve(3, &[10, 5, 2]).unwrap();
+        assert_eq!(operators.iter().map(|o| o.symbol()).collect::<Vec<_>>(), vec!["-", "-"]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let solver = Solver::new().register(Operation::Multiply).register(Operation::Concat);
+        assert!(solver.solve(u64::MAX, &[u64::MAX, 1]).is_some());
+        assert!(solver.solve(u64::MAX, &[u64::MAX, 2]).is_none());
+        assert_eq!(evaluate(&[u64::MAX, 2], &[&Operation::Multiply]), None);
+    }
+}
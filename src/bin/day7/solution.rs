@@ -0,0 +1,171 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "operators.rs"]
+pub mod operators;
+
+pub use utils::Solution;
+use miette::{miette, Result};
+use nom::{bytes::complete::tag, character::complete::{self, line_ending}, multi::separated_list1, sequence::separated_pair, IResult};
+use budget::Exhausted;
+use operators::{Operation, Operator, Solver};
+use rayon::prelude::*;
+use std::fmt::{self, Display};
+pub struct Day7;
+
+pub fn parse(input: &str) -> IResult<&str, Vec<(u64, Vec<u64>)>> {
+    separated_list1(
+        line_ending,
+        separated_pair(
+            complete::u64,
+            tag(": "),
+            separated_list1(
+                tag(" "),
+                 complete::u64
+            )
+    ))(input)
+}
+
+/// A step per equation, the part's budget is shared with the rayon workers
+fn calibrate(equations: &[(u64, Vec<u64>)], solver: &Solver) -> Result<u64> {
+    let budget = budget::current();
+    let solved = equations
+    .par_iter()
+    .map(|(result, numbers)| {
+        budget.step()?;
+        Ok(solver.solve(*result, numbers).map(|operators| (*result, numbers.as_slice(), operators)))
+    })
+    .collect::<Result<Vec<_>, Exhausted>>()?
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    progress::picture("solved equations", SolvedView { solved: &solved });
+
+    solved
+    .iter()
+    .try_fold(0u64, |sum, (result, ..)| sum.checked_add(*result))
+    .ok_or(miette!("The calibration result doesn't fit in 64 bits"))
+}
+
+/// Every equation that could be solved, written out with the operators that solve it
+struct SolvedView<'a> {
+    solved: &'a [(u64, &'a [u64], Vec<&'a dyn Operator>)],
+}
+
+impl Display for SolvedView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (result, numbers, operators) in self.solved {
+            write!(f, "{result} = {}", numbers[0])?;
+            for (operator, number) in operators.iter().zip(&numbers[1..]) {
+                write!(f, " {} {number}", operator.symbol())?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Solution<u64> for Day7 {
+    type Parsed = Vec<(u64, Vec<u64>)>;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let (_, equations) = parse(input).map_err(|e| miette!("Failed to parse equations: {e}"))?;
+
+        Ok(equations)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(equations: &Self::Parsed) -> Result<u64> {
+        let solver = Solver::new()
+            .register(Operation::Add)
+            .register(Operation::Multiply);
+
+        calibrate(equations, &solver)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2(equations: &Self::Parsed) -> Result<u64> {
+        let solver = Solver::new()
+            .register(Operation::Add)
+            .register(Operation::Multiply)
+            .register(Operation::Concat);
+
+        calibrate(equations, &solver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{budget::{Budget, Exhausted}, Day7, Solution};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20"#], super::fuzz::solve::<Day7, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        let equations = Day7::parse("18446744073709551615: 18446744073709551615\n1: 1").unwrap();
+        assert!(Day7::part1(&equations).is_err());
+        // Anything times 0 is 0, so the 3 is left over for the last addition
+        assert_eq!(Day7::part1(&Day7::parse("7: 3 0 7").unwrap()).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_budget_reaches_workers() {
+        let equations = Day7::parse("190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6").unwrap();
+        let error = Budget::unlimited().with_max_steps(3).run(|| Day7::part2(&equations)).unwrap_err();
+        assert_eq!(error.downcast_ref::<Exhausted>(), Some(&Exhausted::Steps(3)));
+        assert_eq!(Budget::unlimited().with_max_steps(4).run(|| Day7::part2(&equations)).unwrap(), 190 + 3267 + 156);
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20"#;
+        let result = Day7::part1(&Day7::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 3749)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20"#;
+        let result = Day7::part2(&Day7::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 11387)
+    }
+}
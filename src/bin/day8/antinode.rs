@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashSet};
+use glam::IVec2;
+use itertools::Itertools;
+use miette::{bail, Result};
+
+/// Where antinodes appear for every pair of same-frequency antennas
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    /// Points beyond each antenna at the given multiples of the antenna distance, part 1 is `Multiples(vec![1])`
+    Multiples(Vec<i32>),
+    /// Every lattice point on the line through both antennas
+    Collinear,
+    /// Lattice points on the line where the distances to the antennas are in the ratio `a:b`
+    /// (in either order), including the ones between the antennas
+    #[allow(dead_code)]
+    Ratio(u32, u32),
+}
+
+/// Rectangle from `min` (inclusive) to `max` (exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub min: IVec2,
+    pub max: IVec2,
+}
+
+impl Region {
+    pub fn new(min: IVec2, max: IVec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_size(width: i32, height: i32) -> Self {
+        Self::new(IVec2::ZERO, IVec2::new(width, height))
+    }
+
+    pub fn contains(&self, point: &IVec2) -> bool {
+        point.cmpge(self.min).all() && point.cmplt(self.max).all()
+    }
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// `start + delta * num / den` if it lands on a lattice point
+fn lattice_point(start: IVec2, delta: IVec2, num: i32, den: i32) -> Option<IVec2> {
+    let scaled = delta * num;
+    if den == 0 || scaled.x % den != 0 || scaled.y % den != 0 {
+        return None;
+    }
+
+    Some(start + scaled / den)
+}
+
+fn pair_antinodes(a: IVec2, b: IVec2, rule: &Rule, region: Option<&Region>) -> Vec<IVec2> {
+    let delta = b - a;
+    match rule {
+        Rule::Multiples(multiples) => multiples.iter()
+            .flat_map(|k| [a - delta * *k, b + delta * *k])
+            .collect(),
+        Rule::Collinear => {
+            let Some(region) = region else {
+                return vec![];
+            };
+            let step = delta / gcd(delta.x, delta.y);
+            let mut points = vec![];
+            let mut current = a;
+            while region.contains(&current) {
+                points.push(current);
+                current += step;
+            }
+            current = a - step;
+            while region.contains(&current) {
+                points.push(current);
+                current -= step;
+            }
+            points
+        },
+        Rule::Ratio(x, y) => {
+            let (x, y) = (*x as i32, *y as i32);
+            [(x, y), (y, x)].into_iter()
+                .flat_map(|(n, m)| {
+                    let internal = lattice_point(a, delta, n, n + m);
+                    let external = if n != m { lattice_point(a, delta, n, n - m) } else { None };
+                    [internal, external]
+                })
+                .flatten()
+                .collect()
+        },
+    }
+}
+
+/// Antinodes per frequency, `region` limits the result and is required for `Rule::Collinear`
+pub fn antinodes(antennas: &BTreeMap<char, Vec<IVec2>>, rule: &Rule, region: Option<&Region>) -> Result<BTreeMap<char, HashSet<IVec2>>> {
+    if *rule == Rule::Collinear && region.is_none() {
+        bail!("Collinear antinodes are infinite without a region");
+    }
+
+    Ok(antennas.iter()
+        .map(|(frequency, coords)| {
+            let points = coords.iter()
+                .tuple_combinations()
+                .flat_map(|(a, b)| pair_antinodes(*a, *b, rule, region))
+                .filter(|p| region.is_none_or(|r| r.contains(p)))
+                .collect::<HashSet<_>>();
+            (*frequency, points)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use std::collections::{BTreeMap, HashSet};
+    #[allow(unused_imports)]
+    use glam::IVec2;
+    #[allow(unused_imports)]
+    use super::{antinodes, Region, Rule};
+
+    #[allow(dead_code)]
+    fn single(a: IVec2, b: IVec2) -> BTreeMap<char, Vec<IVec2>> {
+        BTreeMap::from([('a', vec![a, b])])
+    }
+
+    #[test]
+    fn test_collinear_reduces_step() {
+        let antennas = single(IVec2::new(0, 0), IVec2::new(2, 4));
+        let result = antinodes(&antennas, &Rule::Collinear, Some(&Region::from_size(10, 10))).unwrap();
+        assert_eq!(result[&'a'], HashSet::from([
+            IVec2::new(0, 0),
+            IVec2::new(1, 2),
+            IVec2::new(2, 4),
+            IVec2::new(3, 6),
+            IVec2::new(4, 8),
+        ]));
+        assert!(antinodes(&antennas, &Rule::Collinear, None).is_err());
+    }
+
+    #[test]
+    fn test_multiples_unbounded() {
+        let antennas = single(IVec2::new(1, 1), IVec2::new(2, 3));
+        let result = antinodes(&antennas, &Rule::Multiples(vec![1, 2]), None).unwrap();
+        assert_eq!(result[&'a'], HashSet::from([
+            IVec2::new(0, -1),
+            IVec2::new(-1, -3),
+            IVec2::new(3, 5),
+            IVec2::new(4, 7),
+        ]));
+    }
+
+    #[test]
+    fn test_ratio() {
+        let antennas = single(IVec2::new(0, 0), IVec2::new(3, 3));
+        let result = antinodes(&antennas, &Rule::Ratio(1, 2), None).unwrap();
+        assert_eq!(result[&'a'], HashSet::from([
+            IVec2::new(-3, -3),
+            IVec2::new(1, 1),
+            IVec2::new(2, 2),
+            IVec2::new(6, 6),
+        ]));
+
+        let region = Region::new(IVec2::new(-1, -1), IVec2::new(4, 4));
+        let result = antinodes(&antennas, &Rule::Ratio(1, 1), Some(&region)).unwrap();
+        assert!(result[&'a'].is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+#[path = "../../utils.rs"]
+pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "antinode.rs"]
+pub mod antinode;
+
+pub use utils::Solution;
+use std::collections::{BTreeMap, HashSet};
+use antinode::{antinodes, Region, Rule};
+use glam::IVec2;
+use miette::Result;
+pub struct Day8;
+
+pub fn parse_input(input: &str) -> BTreeMap<char, Vec<IVec2>> {
+    let mut antennas  = BTreeMap::<char, Vec<IVec2>>::new();
+    input.lines().enumerate()
+    .for_each(|(y, line)| line.chars().enumerate().for_each(|(x, c)| match c {
+        'a'..='z' | 'A'..='Z' | '0'..='9'  => {
+            antennas.entry(c).and_modify(|v| v.push(IVec2::new(x as i32, y as i32))).or_insert(vec![IVec2::new(x as i32, y as i32)]);
+        }
+        _ => {}
+    }));
+    antennas
+}
+
+fn count_antinodes(antennas: &BTreeMap<char, Vec<IVec2>>, rule: &Rule, region: &Region) -> Result<u32> {
+    Ok(antinodes(antennas, rule, Some(region))?
+    .into_values()
+    .flatten()
+    .collect::<HashSet<IVec2>>()
+    .len() as u32)
+}
+
+fn map_region(input: &str) -> Region {
+    Region::from_size(input.lines().next().map_or(0, |line| line.chars().count()) as i32, input.lines().count() as i32)
+}
+
+impl Solution<u32> for Day8 {
+    type Parsed = (BTreeMap<char, Vec<IVec2>>, Region);
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok((parse_input(input), map_region(input)))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1((antennas, region): &Self::Parsed) -> Result<u32> {
+        count_antinodes(antennas, &Rule::Multiples(vec![1]), region)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part2((antennas, region): &Self::Parsed) -> Result<u32> {
+        count_antinodes(antennas, &Rule::Collinear, region)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Day8, Solution};
+
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............"#], super::fuzz::solve::<Day8, _>);
+    }
+
+    #[test]
+    fn test_part1() {
+        let test = r#"............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............"#;
+        let result = Day8::part1(&Day8::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 14)
+    }
+
+    #[test]
+    fn test_part2() {
+        let test = r#"............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............"#;
+        let result = Day8::part2(&Day8::parse(test).unwrap());
+        assert_eq!(result.unwrap(), 34)
+    }
+}
@@ -1,42 +1,32 @@
 mod solution;
-use solution::parse_part1;
-use solution::parse_part2;
 use solution::Day9;
 use solution::Solution;
-use miette::Result;
+
+const INPUT: &str = include_str!("../../../inputs/day9.txt");
 
 fn main() {
     divan::main();
 }
 
 #[divan::bench]
-fn bench_part1() -> Result<u64> {
-    Day9::part1(divan::black_box(include_str!(
-        "../../../inputs/day9.txt",
-    )))
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| Day9::parse(divan::black_box(INPUT)));
 }
 
 #[divan::bench]
-fn bench_part2() -> Result<u64> {
-    Day9::part2(divan::black_box(include_str!(
-        "../../../inputs/day9.txt",
-    )))
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = Day9::parse(INPUT).unwrap();
+    bencher.bench(|| Day9::part1(divan::black_box(&parsed)));
 }
 
 #[divan::bench]
-fn bench_parse_part1() -> Result<()> {
-    parse_part1(divan::black_box(include_str!(
-        "../../../inputs/day9.txt",
-    )));
-
-    Ok(())
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = Day9::parse(INPUT).unwrap();
+    bencher.bench(|| Day9::part2(divan::black_box(&parsed)));
 }
 
 #[divan::bench]
-fn bench_parse_part2() -> Result<()> {
-    parse_part2(divan::black_box(include_str!(
-        "../../../inputs/day9.txt",
-    )));
-
-    Ok(())
+fn bench_defragment(bencher: divan::Bencher) {
+    let parsed = Day9::parse(INPUT).unwrap();
+    bencher.bench(|| solution::defragment(divan::black_box(&parsed)));
 }
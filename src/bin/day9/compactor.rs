@@ -0,0 +1,202 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use super::{budget::Exhausted, Block};
+use super::layout::{DiskLayout, Move};
+
+/// Free spans in the puzzle input are at most 9 blocks long, only empty files between them make longer ones
+const MAX_SPAN: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Move single blocks from the end into the leftmost free block (part 1)
+    Blocks,
+    /// Move whole files into the leftmost span that fits them (part 2)
+    Files,
+    /// Move whole files when possible, otherwise split them over the leftmost spans
+    SplitFiles,
+}
+
+/// A piece of a file placed on the disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fragment {
+    id: u32,
+    index: usize,
+    count: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Compactor {
+    /// Min-heaps of span start indices, indexed by span length
+    spans: Vec<BinaryHeap<Reverse<usize>>>,
+    files: Vec<Fragment>,
+    layout: DiskLayout,
+}
+
+impl Compactor {
+    pub fn new(layout: &DiskLayout) -> Self {
+        let runs = layout.runs();
+        let longest = runs.iter().filter(|run| run.r#type == Block::Free).map(|run| run.count as usize).max().unwrap_or(0);
+        let mut spans = vec![BinaryHeap::new(); longest.max(MAX_SPAN) + 1];
+        let mut files = vec![];
+        runs.iter().for_each(|run| match run.r#type {
+            Block::Free => spans[run.count as usize].push(Reverse(run.index)),
+            Block::Used(id) => files.push(Fragment { id, index: run.index, count: run.count }),
+        });
+
+        Self { spans, files, layout: layout.clone() }
+    }
+
+    /// Leftmost span of at least `min_count` blocks that starts before `before`
+    fn leftmost(&self, min_count: u32, before: usize) -> Option<(usize, usize)> {
+        (min_count.max(1) as usize..self.spans.len())
+            .filter_map(|count| self.spans[count].peek().map(|Reverse(index)| (*index, count)))
+            .filter(|(index, _)| *index < before)
+            .min()
+    }
+
+    /// Use the first `used` blocks of a span, the rest goes back to the heaps
+    fn take(&mut self, index: usize, count: usize, used: usize) {
+        self.spans[count].pop();
+        if count > used {
+            self.spans[count - used].push(Reverse(index + used));
+        }
+    }
+
+    /// Move blocks from the end of the file into the leftmost spans before it, returns the part that stays
+    fn move_blocks(&mut self, file: Fragment, moves: &mut Vec<Move>) -> Fragment {
+        let mut file = file;
+        while file.count > 0 {
+            let Some((index, count)) = self.leftmost(1, file.index) else {
+                break;
+            };
+            let used = count.min(file.count as usize);
+            self.take(index, count, used);
+            file.count -= used as u32;
+            moves.push(Move { id: file.id, from: file.index + file.count as usize, to: index, count: used as u32 });
+        }
+
+        file
+    }
+
+    /// Every move made by the strategy, in order
+    pub fn moves(&mut self, strategy: Strategy) -> Vec<Move> {
+        let mut moves = vec![];
+        let files = std::mem::take(&mut self.files);
+
+        files.into_iter().rev().for_each(|file| {
+            let whole = match strategy {
+                Strategy::Files | Strategy::SplitFiles => self.leftmost(file.count, file.index),
+                Strategy::Blocks => None,
+            };
+            match (strategy, whole) {
+                (_, Some((index, count))) => {
+                    self.take(index, count, file.count as usize);
+                    moves.push(Move { id: file.id, from: file.index, to: index, count: file.count });
+                },
+                (Strategy::Files, None) => {},
+                (Strategy::Blocks | Strategy::SplitFiles, None) => {
+                    self.move_blocks(file, &mut moves);
+                },
+            }
+        });
+
+        moves
+    }
+
+    /// The compacted layout, `on_step` sees the layout after every step and can stop the compaction,
+    /// `Strategy::Blocks` moves one block at a time like the puzzle walkthrough
+    pub fn compact(mut self, strategy: Strategy, mut on_step: impl FnMut(&DiskLayout) -> Result<(), Exhausted>) -> Result<DiskLayout, Exhausted> {
+        let moves = self.moves(strategy);
+        let mut layout = self.layout;
+        moves.into_iter()
+            .flat_map(|step| match strategy {
+                Strategy::Blocks => (0..step.count as usize)
+                    .map(|i| Move { from: step.from + step.count as usize - 1 - i, to: step.to + i, count: 1, ..step })
+                    .collect(),
+                Strategy::Files | Strategy::SplitFiles => vec![step],
+            })
+            .try_for_each(|step| {
+                layout.apply(&step);
+                on_step(&layout)
+            })?;
+
+        Ok(layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Compactor, Strategy};
+    #[allow(unused_imports)]
+    use super::super::layout::DiskLayout;
+
+    #[allow(dead_code)]
+    fn compact(dense: &str, strategy: Strategy) -> DiskLayout {
+        Compactor::new(&DiskLayout::from_dense(dense)).compact(strategy, |_| Ok(())).unwrap()
+    }
+
+    /// The layout after every step of compacting
+    #[allow(dead_code)]
+    fn steps(dense: &str, strategy: Strategy) -> Vec<String> {
+        let mut steps = vec![];
+        Compactor::new(&DiskLayout::from_dense(dense)).compact(strategy, |layout| {
+            steps.push(layout.to_string());
+            Ok(())
+        }).unwrap();
+
+        steps
+    }
+
+    #[test]
+    fn test_blocks() {
+        let disk = compact("2333133121414131402", Strategy::Blocks);
+        assert_eq!(disk.to_string(), "0099811188827773336446555566..............");
+        assert_eq!(disk.checksum(), 1928);
+    }
+
+    #[test]
+    fn test_files() {
+        let disk = compact("2333133121414131402", Strategy::Files);
+        assert_eq!(disk.to_string(), "00992111777.44.333....5555.6666.....8888..");
+        assert_eq!(disk.checksum(), 2858);
+        assert_eq!(disk.to_dense(), None);
+    }
+
+    #[test]
+    fn test_split_files() {
+        // File 2 fits whole into the second span, so it isn't split into the first one
+        assert_eq!(compact("11122", Strategy::SplitFiles).to_string(), "01.22..");
+        assert_eq!(compact("11122", Strategy::Blocks).to_string(), "0212...");
+
+        // No span fits file 2, so it is split over every span before it
+        assert_eq!(compact("11113", Strategy::SplitFiles).to_string(), "02122..");
+        assert_eq!(compact("11113", Strategy::Files).to_string(), "01..222");
+    }
+
+    #[test]
+    fn test_merged_spans() {
+        // The empty file 3 joins the spans of 3 and 7 blocks around it into one of 10
+        let disk = compact("90563307", Strategy::Files);
+        assert_eq!(disk.to_string(), "00000000011111222................");
+        assert_eq!(disk.checksum(), 145);
+    }
+
+    #[test]
+    fn test_walkthrough() {
+        assert_eq!(steps("12345", Strategy::Blocks), vec![
+            "02.111....2222.",
+            "022111....222..",
+            "0221112...22...",
+            "02211122..2....",
+            "022111222......",
+        ]);
+
+        assert_eq!(steps("2333133121414131402", Strategy::Files), vec![
+            "0099.111...2...333.44.5555.6666.777.8888..",
+            "0099.1117772...333.44.5555.6666.....8888..",
+            "0099.111777244.333....5555.6666.....8888..",
+            "00992111777.44.333....5555.6666.....8888..",
+        ]);
+    }
+}
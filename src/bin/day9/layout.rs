@@ -0,0 +1,137 @@
+use std::fmt::Display;
+
+use super::{parse_runs, Block, DataBlock};
+
+/// Blocks moved by a single compaction step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub id: u32,
+    pub from: usize,
+    pub to: usize,
+    pub count: u32,
+}
+
+/// Disk contents that convert between the dense digit string, single blocks and runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskLayout {
+    blocks: Vec<Block>,
+}
+
+impl DiskLayout {
+    pub fn from_dense(input: &str) -> Self {
+        Self::from_runs(&parse_runs(input.trim()))
+    }
+
+    pub fn from_blocks(blocks: Vec<Block>) -> Self {
+        Self { blocks }
+    }
+
+    pub fn from_runs(runs: &[DataBlock]) -> Self {
+        let size = runs.iter().map(|r| r.index + r.count as usize).max().unwrap_or(0);
+        let mut blocks = vec![Block::Free; size];
+        runs.iter().for_each(|run| {
+            blocks[run.index..run.index + run.count as usize].fill(run.r#type);
+        });
+
+        Self::from_blocks(blocks)
+    }
+
+    /// Every block of the disk in order
+    #[allow(dead_code)]
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Consecutive equal blocks merged into runs
+    pub fn runs(&self) -> Vec<DataBlock> {
+        let mut runs: Vec<DataBlock> = vec![];
+        self.blocks.iter().enumerate().for_each(|(index, block)| match runs.last_mut() {
+            Some(run) if run.r#type == *block => run.count += 1,
+            _ => runs.push(DataBlock { r#type: *block, count: 1, index }),
+        });
+
+        runs
+    }
+
+    /// The dense form, only exists while files are whole, in id order with none left empty and no span is longer than 9
+    #[allow(dead_code)]
+    pub fn to_dense(&self) -> Option<String> {
+        let mut dense = String::new();
+        let mut next_id = 0;
+        let mut expect_file = true;
+        for run in self.runs() {
+            match run.r#type {
+                Block::Used(id) if id == next_id => {
+                    if !expect_file {
+                        dense.push('0');
+                    }
+                    dense.push(char::from_digit(run.count, 10)?);
+                    next_id += 1;
+                    expect_file = false;
+                },
+                Block::Free if !expect_file => {
+                    dense.push(char::from_digit(run.count, 10)?);
+                    expect_file = true;
+                },
+                _ => return None,
+            }
+        }
+
+        Some(dense)
+    }
+
+    pub fn checksum(&self) -> u64 {
+        self.blocks.iter().enumerate().map(|(pos, block)| match block {
+            Block::Used(id) => pos as u64 * *id as u64,
+            Block::Free => 0,
+        }).sum()
+    }
+
+    pub fn apply(&mut self, step: &Move) {
+        let count = step.count as usize;
+        self.blocks[step.from..step.from + count].fill(Block::Free);
+        self.blocks[step.to..step.to + count].fill(Block::Used(step.id));
+    }
+}
+
+/// The puzzle's `00...111...2...333.44` notation, ids above 9 only show their last digit
+impl Display for DiskLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.blocks.iter().try_for_each(|block| match block {
+            Block::Used(id) => write!(f, "{}", id % 10),
+            Block::Free => write!(f, "."),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::DiskLayout;
+
+    #[test]
+    fn test_round_trip() {
+        let layout = DiskLayout::from_dense("2333133121414131402");
+        assert_eq!(layout.to_string(), "00...111...2...333.44.5555.6666.777.888899");
+        assert_eq!(layout.to_dense().unwrap(), "2333133121414131402");
+        assert_eq!(DiskLayout::from_runs(&layout.runs()), layout);
+        assert_eq!(DiskLayout::from_blocks(layout.blocks().to_vec()), layout);
+    }
+
+    #[test]
+    fn test_dense_edge_cases() {
+        assert_eq!(DiskLayout::from_dense("10203").to_dense().unwrap(), "10203");
+        assert_eq!(DiskLayout::from_dense("10203").to_string(), "011222");
+        assert_eq!(DiskLayout::from_dense("12").to_dense().unwrap(), "12");
+        assert_eq!(DiskLayout::from_dense("12").to_string(), "0..");
+        assert_eq!(DiskLayout::from_dense("10011").to_dense(), None);
+        assert_eq!(DiskLayout::from_runs(&DiskLayout::from_dense("1211").runs()).to_string(), "0..1.");
+    }
+
+    #[test]
+    fn test_checksum() {
+        let layout = DiskLayout::from_dense("12345");
+        assert_eq!(layout.to_string(), "0..111....22222");
+        assert_eq!(layout.checksum(), 3 + 4 + 5 + 2 * (10 + 11 + 12 + 13 + 14));
+    }
+}
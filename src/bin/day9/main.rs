@@ -1,14 +1,20 @@
 mod solution;
+#[path = "../../telemetry.rs"]
+mod telemetry;
 use solution::Day9;
 use solution::Solution;
+use solution::budget::{self, Budget};
 use miette::Result;
 
-#[tracing::instrument]
-fn main() -> Result<()>{
-    tracing_subscriber::fmt::init();
+fn main() -> Result<()> {
+    let _telemetry = telemetry::init();
+    budget::cancel_on_interrupt();
+    let budget = Budget::from_env()?;
     let input = include_str!("../../../inputs/day9.txt");
-    println!("Part 1: {:?}", Day9::part1(input)?);
-    println!("Part 2: {:?}", Day9::part2(input)?);
+    let parsed = Day9::parse(input)?;
+    println!("Part 1: {:?}", budget.run(|| Day9::part1(&parsed)));
+    println!("Part 2: {:?}", budget.run(|| Day9::part2(&parsed)));
+    println!("Defragmented: {:?}", budget.run(|| solution::defragment(&parsed)));
 
     Ok(())
 }
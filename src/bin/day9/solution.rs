@@ -1,10 +1,28 @@
 #[path = "../../utils.rs"]
 pub mod utils;
-
-use std::collections::BTreeSet;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+#[path = "compactor.rs"]
+pub mod compactor;
+#[path = "layout.rs"]
+pub mod layout;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../property.rs"]
+mod property;
 
 pub use utils::Solution;
-use miette::Result;
+use compactor::{Compactor, Strategy};
+use layout::DiskLayout;
+use miette::{miette, Result};
 pub struct Day9;
 
 #[derive(Debug, PartialEq, Clone, Eq, Copy)]
@@ -15,79 +33,31 @@ pub enum Block {
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct DataBlock {
-    r#type: Block,
-    count: u32,
-    index: usize,
-}
-
-impl PartialOrd for DataBlock {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for DataBlock {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.index.cmp(&other.index)
-    }
+    pub r#type: Block,
+    pub count: u32,
+    pub index: usize,
 }
 
-impl DataBlock {
-    pub fn new(block: Block, count: u32, index: usize) -> Self {
-        Self {
-            r#type: block,
-            count,
-            index,
-        }
-    }
-}
-
-pub fn parse_part1(input: &str) -> Vec<Block> {
+/// Runs of the dense disk map, lengths of 0 are left out but every file digit counts for the ids
+pub fn parse_runs(input: &str) -> Vec<DataBlock> {
     let mut free_space = false;
-    let mut blocks = Vec::with_capacity(100_000);
-    let mut block_id = 0;
-    input.chars().for_each(|c| {
-        if free_space {
-            for _ in 0..c.to_digit(10).unwrap() {
-                blocks.push(Block::Free);
-            }
-        } else {
-            for _ in 0..c.to_digit(10).unwrap() {
-                blocks.push(Block::Used(block_id));
-            }
-            block_id += 1;
-        }
-
-        free_space = !free_space;
-    });
-
-    blocks
-}
-
-pub fn parse_part2(input: &str) -> Vec<DataBlock> {
-    let mut free_space = false;
-    let mut blocks = Vec::with_capacity(100_000);
+    let mut blocks = Vec::with_capacity(input.len());
     let mut block_id = 0;
     let mut real_index = 0_usize;
     input.chars().for_each(|c|{
         let count = c.to_digit(10).unwrap();
         if count != 0 {
-            if free_space {
-                blocks.push(DataBlock {
-                    r#type: Block::Free,
-                    count,
-                    index: real_index,
-                });
-            } else {
-                blocks.push(DataBlock {
-                    r#type: Block::Used(block_id),
-                    count,
-                    index: real_index,
-                });
-                block_id += 1;
-            }
+            blocks.push(DataBlock {
+                r#type: if free_space { Block::Free } else { Block::Used(block_id) },
+                count,
+                index: real_index,
+            });
             real_index += count as usize;
         }
+        // Empty files still take up an id
+        if !free_space {
+            block_id += 1;
+        }
 
         free_space = !free_space;
     });
@@ -96,91 +66,41 @@ pub fn parse_part2(input: &str) -> Vec<DataBlock> {
 }
 
 impl Solution<u64> for Day9 {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u64> {
-        let mut checksum: u64 = 0;
-        let blocks = parse_part1(input);
-        let iter = blocks.iter().enumerate();
-        let mut reverse_iter = iter.clone().rev();
-        let mut rev_pos: Option<usize> = None;
-        for (pos, block) in iter {
-            if rev_pos.is_some() && rev_pos.unwrap() <= pos {
-                break;
-            }
-            match block {
-                Block::Used(id) => {
-                    checksum += pos as u64 * *id as u64;
-                },
-                Block::Free => {
-                    // Use while here to allow stopping at any point
-                    #[allow(clippy::while_let_on_iterator)]
-                    while let Some((new_rev_pos, block)) = reverse_iter.next() {
-                        if new_rev_pos <= pos {
-                            break;
-                        }
-                        match block {
-                            Block::Used(id) => {
-                                rev_pos = Some(new_rev_pos);
-                                checksum += pos as u64 * *id as u64;
-                                break;
-                            },
-                            Block::Free => {},
-                        }
-                    }
-                }
-            }
+    type Parsed = DiskLayout;
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        if let Some((i, c)) = input.trim().char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            return Err(miette!("Length {c:?} at {i} isn't a digit"));
         }
 
-        Ok(checksum)
+        Ok(DiskLayout::from_dense(input))
     }
 
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u64> {
-        let blocks = parse_part2(input);
-        let mut moved_blocks = BTreeSet::new();
-        let mut spaces = blocks.iter().filter(|b| b.r#type == Block::Free).cloned().collect::<BTreeSet<_>>();
-
-        for block in blocks.iter().rev() {
-            let mut to_add: Option<DataBlock> = None;
-            let mut to_remove: Option<DataBlock> = None;
-            match block.r#type {
-                Block::Used(_) => {
-                    if let Some(empty_block) = spaces.iter().find(|b| b.r#type == Block::Free && b.index < block.index && b.count >= block.count) {
-                        let diff = empty_block.count - block.count;
-                        moved_blocks.insert(DataBlock::new(block.r#type, block.count, empty_block.index));
-                        moved_blocks.insert(DataBlock::new(Block::Free, block.count, block.index));
-
-                        if diff > 0 {
-                            to_add = Some(DataBlock::new(Block::Free, diff, empty_block.index + block.count as usize));
-                        }
-
-                        to_remove = Some(empty_block.clone());
-                    } else {
-                        // No position found, just move the block to the end
-                        moved_blocks.insert(DataBlock::new(block.r#type, block.count, block.index));
-                    }
-                },
-                Block::Free => {
-                    moved_blocks.insert(DataBlock::new(block.r#type, block.count, block.index));
-                },
-            }
+    #[tracing::instrument(skip_all)]
+    fn part1(layout: &Self::Parsed) -> Result<u64> {
+        let budget = budget::current();
+        let disk = Compactor::new(layout).compact(Strategy::Blocks, |_| budget.step())?;
 
-            if let Some(add) = to_add {
-                spaces.insert(add);
-            }
-            if let Some(remove) = to_remove {
-                spaces.remove(&remove);
-            }
-        }
+        Ok(disk.checksum())
+    }
 
-        Ok(moved_blocks.into_iter().map(|b| {
-            (b.index..b.index + b.count as usize).map(|i| i as u64).sum::<u64>() * match b.r#type {
-                Block::Used(id) => id as u64,
-                Block::Free => 0,
-            }
-        }).sum())
+    #[tracing::instrument(skip_all)]
+    fn part2(layout: &Self::Parsed) -> Result<u64> {
+        let budget = budget::current();
+        let disk = Compactor::new(layout).compact(Strategy::Files, |_| budget.step())?;
+
+        Ok(disk.checksum())
     }
-    
+}
+
+/// Checksum once files that fit are moved whole and the rest are split over free spans, beyond the puzzle
+#[tracing::instrument(skip_all)]
+pub fn defragment(layout: &DiskLayout) -> Result<u64> {
+    let budget = budget::current();
+    let disk = Compactor::new(layout).compact(Strategy::SplitFiles, |_| budget.step())?;
+
+    Ok(disk.checksum())
 }
 
 
@@ -188,34 +108,42 @@ impl Solution<u64> for Day9 {
 mod tests {
     #[allow(unused_imports)]
     use super::*;
+    #[allow(unused_imports)]
+    use super::property::check;
+
+    /// Expand every block and move the last used block into the first free one until they meet
+    #[allow(dead_code)]
+    fn compact_naive(digits: &[u8]) -> u64 {
+        let mut disk = digits.iter().enumerate().flat_map(|(i, count)| {
+            let block = if i % 2 == 0 { Some(i as u64 / 2) } else { None };
+            std::iter::repeat_n(block, *count as usize)
+        }).collect::<Vec<_>>();
+        while let Some(free) = disk.iter().position(Option::is_none) {
+            match disk.iter().rposition(Option::is_some) {
+                Some(used) if used > free => disk.swap(free, used),
+                _ => break,
+            }
+        }
+
+        disk.iter().enumerate().map(|(pos, block)| pos as u64 * block.unwrap_or(0)).sum()
+    }
 
     #[test]
-    fn test_parse_1() {
-        let test = r#"12345"#;
-        let result = parse_part1(test);
-        assert_eq!(result, vec![
-            Block::Used(0),
-            Block::Free,
-            Block::Free,
-            Block::Used(1),
-            Block::Used(1),
-            Block::Used(1),
-            Block::Free,
-            Block::Free,
-            Block::Free,
-            Block::Free,
-            Block::Used(2),
-            Block::Used(2),
-            Block::Used(2),
-            Block::Used(2),
-            Block::Used(2),
-        ]);
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#"12345"#], super::fuzz::solve::<Day9, _>);
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        assert!(Day9::parse("<").is_err());
+        assert!(Day9::parse("").is_ok());
+        assert_eq!(Day9::part2(&Day9::parse("90563307").unwrap()).unwrap(), 145);
     }
 
     #[test]
-    fn test_parse_2() {
+    fn test_parse_runs() {
         let test = r#"12345"#;
-        let result = parse_part2(test);
+        let result = parse_runs(test);
         assert_eq!(result, vec![
             DataBlock { r#type: Block::Used(0), count: 1, index: 0 },
             DataBlock { r#type: Block::Free, count: 2, index: 1 },
@@ -223,19 +151,50 @@ mod tests {
             DataBlock { r#type: Block::Free, count: 4, index: 6 },
             DataBlock { r#type: Block::Used(2), count: 5, index: 10 },
         ]);
+
+        assert_eq!(parse_runs("10011"), vec![
+            DataBlock { r#type: Block::Used(0), count: 1, index: 0 },
+            DataBlock { r#type: Block::Free, count: 1, index: 1 },
+            DataBlock { r#type: Block::Used(2), count: 1, index: 2 },
+        ]);
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let layout = Day9::parse("10011").unwrap();
+        assert_eq!(layout.to_string(), "0.2");
+        assert_eq!(Day9::part1(&layout).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_part1_matches_naive() {
+        check(
+            |rng| (0..rng.range(1..=20)).map(|_| rng.range(0..=9) as u8).collect::<Vec<_>>(),
+            |digits| {
+                let dense = digits.iter().map(|digit| digit.to_string()).collect::<String>();
+                Day9::part1(&Day9::parse(&dense).unwrap()).unwrap() == compact_naive(digits)
+            },
+        );
     }
 
     #[test]
     fn test_part1() {
         let test = r#"2333133121414131402"#;
-        let result = Day9::part1(test.trim());
+        let result = Day9::part1(&Day9::parse(test.trim()).unwrap());
         assert_eq!(result.unwrap(), 1928);
     }
 
+    #[test]
+    fn test_defragment() {
+        // `02122..`, file 2 fits nowhere whole so it is split over both spans
+        let result = defragment(&Day9::parse("11113").unwrap());
+        assert_eq!(result.unwrap(), 2 + 2 + 3 * 2 + 4 * 2);
+    }
+
     #[test]
     fn test_part2() {
         let test = r#"2333133121414131402"#;
-        let result = Day9::part2(test.trim());
+        let result = Day9::part2(&Day9::parse(test.trim()).unwrap());
         assert_eq!(result.unwrap(), 2858)
     }
 }
@@ -1,22 +1,26 @@
 mod solution;
 use solution::DayX;
 use solution::Solution;
-use miette::Result;
+
+const INPUT: &str = include_str!("../../../inputs/dayX.txt");
 
 fn main() {
     divan::main();
 }
 
 #[divan::bench]
-fn bench_part1() -> Result<u32> {
-   DayX::part1(divan::black_box(include_str!(
-        "../../../inputs/dayX.txt",
-    )))
+fn bench_parse(bencher: divan::Bencher) {
+    bencher.bench(|| DayX::parse(divan::black_box(INPUT)));
 }
 
 #[divan::bench]
-fn bench_part2() -> Result<u32> {
-    DayX::part2(divan::black_box(include_str!(
-        "../../../inputs/dayX.txt",
-    )))
-}
\ No newline at end of file
+fn bench_part1(bencher: divan::Bencher) {
+    let parsed = DayX::parse(INPUT).unwrap();
+    bencher.bench(|| DayX::part1(divan::black_box(&parsed)));
+}
+
+#[divan::bench]
+fn bench_part2(bencher: divan::Bencher) {
+    let parsed = DayX::parse(INPUT).unwrap();
+    bencher.bench(|| DayX::part2(divan::black_box(&parsed)));
+}
@@ -1,9 +1,19 @@
 mod solution;
+#[path = "../../telemetry.rs"]
+mod telemetry;
 use solution::DayX;
 use solution::Solution;
+use solution::budget::{self, Budget};
+use miette::Result;
 
-fn main() {
+fn main() -> Result<()> {
+    let _telemetry = telemetry::init();
+    budget::cancel_on_interrupt();
+    let budget = Budget::from_env()?;
     let input = include_str!("../../../inputs/dayX.txt");
-    println!("Part 1: {:?}", DayX::part1(input));
-    println!("Part 2: {:?}", DayX::part2(input));
+    let parsed = DayX::parse(input)?;
+    println!("Part 1: {:?}", budget.run(|| DayX::part1(&parsed)));
+    println!("Part 2: {:?}", budget.run(|| DayX::part2(&parsed)));
+
+    Ok(())
 }
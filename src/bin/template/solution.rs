@@ -1,17 +1,35 @@
 #[path = "../../utils.rs"]
 pub mod utils;
+#[allow(dead_code)]
+#[path = "../../budget.rs"]
+pub mod budget;
+#[allow(dead_code)]
+#[path = "../../progress.rs"]
+pub mod progress;
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fuzz.rs"]
+mod fuzz;
+
 pub use utils::Solution;
 use miette::Result;
 pub struct DayX;
 
 impl Solution<u32> for DayX {
-    #[tracing::instrument]
-    fn part1(input: &str) -> Result<u32> {
+    type Parsed = ();
+
+    #[tracing::instrument(skip_all)]
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn part1(parsed: &Self::Parsed) -> Result<u32> {
         Ok(0)
     }
     
-    #[tracing::instrument]
-    fn part2(input: &str) -> Result<u32> {
+    #[tracing::instrument(skip_all)]
+    fn part2(parsed: &Self::Parsed) -> Result<u32> {
         Ok(0)
     }
 }
@@ -21,17 +39,22 @@ mod tests {
     #[allow(unused_imports)]
     use super::{DayX, Solution};
 
+    #[test]
+    fn test_fuzz() {
+        super::fuzz::fuzz(&[r#""#], super::fuzz::solve::<DayX, _>);
+    }
+
     #[test]
     fn test_part1() {
         let test = r#""#;
-        let result = DayX::part1(test);
+        let result = DayX::part1(&DayX::parse(test).unwrap());
         assert_eq!(result.unwrap(), 0)
     }
 
     #[test]
     fn test_part2() {
         let test = r#""#;
-        let result = DayX::part2(test);
+        let result = DayX::part2(&DayX::parse(test).unwrap());
         assert_eq!(result.unwrap(), 0)
     }
 }
@@ -0,0 +1,199 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use miette::{miette, Diagnostic, IntoDiagnostic, Result};
+
+/// Seconds a part may run for
+pub const TIMEOUT_VAR: &str = "AOC_TIMEOUT";
+/// Steps a part may take, what a step is depends on the solution
+pub const MAX_STEPS_VAR: &str = "AOC_MAX_STEPS";
+/// Steps between clock reads, `Instant::now` is too slow for every step of a hot loop
+const CLOCK_INTERVAL: u64 = 1024;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CURRENT: RefCell<Option<Budget>> = const { RefCell::new(None) };
+}
+
+/// Why a solution had to stop early
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exhausted {
+    Timeout(Duration),
+    Steps(u64),
+    Cancelled,
+}
+
+impl Display for Exhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exhausted::Timeout(timeout) => write!(f, "Timed out after {timeout:?}"),
+            Exhausted::Steps(steps) => write!(f, "Gave up after {steps} steps"),
+            Exhausted::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Exhausted {}
+
+impl Diagnostic for Exhausted {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new("budget::exhausted"))
+    }
+}
+
+/// Limits a part has to stay within, solutions check them cooperatively in their long loops.
+/// Parts that hand work to rayon pass `current()` into the closures, the workers have no current budget of their own.
+/// Single bounded passes over the input, like most of days 1 to 13 and day 16's A*, aren't limited
+#[derive(Debug, Clone)]
+pub struct Budget {
+    timeout: Option<Duration>,
+    max_steps: Option<u64>,
+    started: Instant,
+    steps: Arc<AtomicU64>,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Self { timeout: None, max_steps: None, started: Instant::now(), steps: Arc::default() }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Limits from `AOC_TIMEOUT` and `AOC_MAX_STEPS`, unlimited when they aren't set
+    pub fn from_env() -> Result<Self> {
+        let mut budget = Self::unlimited();
+        if let Ok(timeout) = std::env::var(TIMEOUT_VAR) {
+            let seconds = timeout.parse::<f64>().into_diagnostic()?;
+            budget = budget.with_timeout(Duration::try_from_secs_f64(seconds).map_err(|e| miette!("{TIMEOUT_VAR}={timeout}: {e}"))?);
+        }
+        if let Ok(max_steps) = std::env::var(MAX_STEPS_VAR) {
+            budget = budget.with_max_steps(max_steps.parse().into_diagnostic()?);
+        }
+
+        Ok(budget)
+    }
+
+    /// The same limits with the clock and step count starting over
+    fn restart(&self) -> Self {
+        Self { started: Instant::now(), steps: Arc::default(), ..self.clone() }
+    }
+
+    /// Fails once the time is up or the run was interrupted
+    pub fn check(&self) -> Result<(), Exhausted> {
+        if INTERRUPTED.load(Ordering::Relaxed) {
+            return Err(Exhausted::Cancelled);
+        }
+        match self.timeout {
+            Some(timeout) if self.started.elapsed() > timeout => Err(Exhausted::Timeout(timeout)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Count a step of the solution, fails once the steps or the time are used up
+    pub fn step(&self) -> Result<(), Exhausted> {
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(max_steps) = self.max_steps.filter(|max_steps| steps > *max_steps) {
+            return Err(Exhausted::Steps(max_steps));
+        }
+        if steps.is_multiple_of(CLOCK_INTERVAL) {
+            self.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Run a part with a fresh copy of the limits as the thread's current budget
+    pub fn run<T>(&self, part: impl FnOnce() -> Result<T>) -> Result<T> {
+        if interrupted() {
+            return Err(Exhausted::Cancelled.into());
+        }
+        let budget = self.restart();
+        let previous = CURRENT.with(|current| current.replace(Some(budget)));
+        let result = part();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+
+        result
+    }
+}
+
+/// Budget of the part running on this thread, unlimited outside of `Budget::run`
+pub fn current() -> Budget {
+    CURRENT.with(|current| current.borrow().clone()).unwrap_or_default()
+}
+
+/// Whether Ctrl-C was pressed since the last `clear_interrupt`
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+pub fn clear_interrupt() {
+    INTERRUPTED.store(false, Ordering::Relaxed);
+}
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    // A second Ctrl-C stops solutions that never check their budget
+    if INTERRUPTED.swap(true, Ordering::Relaxed) {
+        unsafe { libc::_exit(130) };
+    }
+}
+
+/// Turn the first Ctrl-C into `Exhausted::Cancelled` for the running part instead of killing the process
+pub fn cancel_on_interrupt() {
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGINT, on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use std::time::Duration;
+    #[allow(unused_imports)]
+    use super::{current, Budget, Exhausted};
+
+    #[test]
+    fn test_steps() {
+        let budget = Budget::unlimited().with_max_steps(3);
+        assert!((0..3).all(|_| budget.step().is_ok()));
+        assert_eq!(budget.step(), Err(Exhausted::Steps(3)));
+    }
+
+    #[test]
+    fn test_run() {
+        let budget = Budget::unlimited().with_max_steps(1).with_timeout(Duration::ZERO);
+        let error = budget.run(|| {
+            current().step()?;
+            current().step()?;
+            Ok(())
+        }).unwrap_err();
+        assert_eq!(error.downcast_ref::<Exhausted>(), Some(&Exhausted::Steps(1)));
+
+        let error = budget.run(|| {
+            std::thread::sleep(Duration::from_millis(1));
+            current().check().map_err(Into::into)
+        }).unwrap_err();
+        assert_eq!(error.downcast_ref::<Exhausted>(), Some(&Exhausted::Timeout(Duration::ZERO)));
+        assert!(current().step().is_ok());
+    }
+}
@@ -0,0 +1,157 @@
+use std::{panic::{self, catch_unwind, AssertUnwindSafe, PanicHookInfo}, sync::Arc, thread};
+use super::{budget::Budget, utils::Solution};
+
+// Days using both harnesses load their own copy through `property.rs`
+#[allow(dead_code, clippy::duplicate_mod)]
+#[path = "rng.rs"]
+mod rng;
+use rng::Rng;
+
+/// Inputs per target, `FUZZ_RUNS` overrides it
+const RUNS: usize = 1000;
+/// Seed of the mutations, `FUZZ_SEED` overrides it to replay a run
+const SEED: u64 = 2024;
+/// Steps a part may take on a fuzzed input, parts without a budget check aren't limited
+const MAX_STEPS: u64 = 10_000;
+/// Bytes mutations insert besides the ones already in the seeds
+const DICTIONARY: &[u8] = b"0123456789-+,:=|\n \t#.^<>v@[]OX";
+
+fn setting<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+fn crashes(target: &impl Fn(&[u8]), data: &[u8]) -> bool {
+    catch_unwind(AssertUnwindSafe(|| target(data))).is_err()
+}
+
+fn mutate(rng: &mut Rng, seeds: &[&[u8]], data: &mut Vec<u8>) {
+    let byte = |rng: &mut Rng, data: &[u8]| if data.is_empty() || rng.chance(0.5) { *rng.pick(DICTIONARY) } else { *rng.pick(data) };
+    let at = |rng: &mut Rng, len: usize| rng.below(len + 1);
+    match rng.below(7) {
+        0 if !data.is_empty() => {
+            let i = at(rng, data.len() - 1);
+            data[i] = byte(rng, data);
+        },
+        1 => {
+            let (i, value) = (at(rng, data.len()), byte(rng, data));
+            data.insert(i, value);
+        },
+        2 if !data.is_empty() => {
+            let start = at(rng, data.len() - 1);
+            let end = (start + 1 + rng.below(8)).min(data.len());
+            data.drain(start..end);
+        },
+        3 => data.truncate(at(rng, data.len())),
+        4 if !data.is_empty() => {
+            let start = at(rng, data.len() - 1);
+            let end = (start + 1 + rng.below(16)).min(data.len());
+            let copy = data[start..end].to_vec();
+            let i = at(rng, data.len());
+            data.splice(i..i, copy);
+        },
+        5 => {
+            let digits = rng.range(1..=20) as usize;
+            let number = (0..digits).map(|_| b'0' + rng.below(10) as u8).collect::<Vec<_>>();
+            let i = at(rng, data.len());
+            data.splice(i..i, number);
+        },
+        _ => {
+            let other = rng.pick(seeds);
+            let i = at(rng, data.len());
+            data.truncate(i);
+            data.extend_from_slice(&other[at(rng, other.len())..]);
+        },
+    }
+}
+
+/// Remove chunks, then single bytes, as long as the input still crashes
+fn minimize(target: &impl Fn(&[u8]), mut data: Vec<u8>) -> Vec<u8> {
+    let mut chunk = data.len().div_ceil(2).max(1);
+    loop {
+        let mut i = 0;
+        while i < data.len() {
+            let mut smaller = data.clone();
+            smaller.drain(i..(i + chunk).min(data.len()));
+            if crashes(target, &smaller) {
+                data = smaller;
+            } else {
+                i += chunk;
+            }
+        }
+        if chunk == 1 {
+            return data;
+        }
+        chunk /= 2;
+    }
+}
+
+/// Run a libFuzzer-style target on mutations of the seeds and panic with the smallest crashing input found,
+/// ready to be pasted into a regression test
+pub fn fuzz(seeds: &[&str], target: impl Fn(&[u8])) {
+    let seeds = seeds.iter().map(|seed| seed.as_bytes()).collect::<Vec<_>>();
+    let seed = setting("FUZZ_SEED", SEED);
+    let mut rng = Rng::new(seed);
+
+    // Every caught crash would print its panic message, so they are only shown for other threads until the run is over
+    let previous: Arc<dyn Fn(&PanicHookInfo) + Send + Sync> = Arc::from(panic::take_hook());
+    let fuzzing = thread::current().id();
+    let hook = previous.clone();
+    panic::set_hook(Box::new(move |info| if thread::current().id() != fuzzing { hook(info) }));
+    let crash = (0..setting("FUZZ_RUNS", RUNS)).find_map(|run| {
+        let mut data = rng.pick(&seeds).to_vec();
+        (0..rng.range(1..=4)).for_each(|_| mutate(&mut rng, &seeds, &mut data));
+        crashes(&target, &data).then(|| {
+            let minimal = minimize(&target, data.clone());
+            (run, data, minimal)
+        })
+    });
+    panic::set_hook(Box::new(move |info| previous(info)));
+
+    if let Some((run, data, minimal)) = crash {
+        panic!(
+            "Crash in run {run} of seed {seed}\ninput: {:?}\nminimized: {:?}",
+            String::from_utf8_lossy(&data),
+            String::from_utf8_lossy(&minimal),
+        );
+    }
+}
+
+/// Parse the input and, when that succeeds, run both parts, any failure has to be an `Err`
+pub fn solve<S: Solution<T>, T>(data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else { return };
+    if let Ok(parsed) = S::parse(input) {
+        let budget = Budget::unlimited().with_max_steps(MAX_STEPS);
+        let _ = budget.run(|| S::part1(&parsed));
+        let _ = budget.run(|| S::part2(&parsed));
+    }
+}
+
+/// Parse the input and run part 1, for days whose part 2 is too slow to run on every input
+pub fn part1<S: Solution<T>, T>(data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else { return };
+    if let Ok(parsed) = S::parse(input) {
+        let _ = Budget::unlimited().with_max_steps(MAX_STEPS).run(|| S::part1(&parsed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{fuzz, minimize};
+
+    #[allow(dead_code)]
+    fn no_sevens(data: &[u8]) {
+        assert!(!data.contains(&b'7'));
+    }
+
+    #[test]
+    fn test_minimize() {
+        assert_eq!(minimize(&no_sevens, b"12\n3474\n57".to_vec()), b"7");
+    }
+
+    #[test]
+    #[should_panic(expected = "minimized: \"7\"")]
+    fn test_fuzz_reports() {
+        fuzz(&["123 456"], no_sevens);
+    }
+}
@@ -0,0 +1,22 @@
+use std::fmt::Display;
+
+/// Target of every progress event, `telemetry` renders these as a bar, log lines or nothing
+pub const TARGET: &str = "progress";
+
+/// `done` units of work out of `total`, leave `total` out for searches with no known end
+pub fn advance(label: &'static str, done: u64, total: Option<u64>) {
+    match total {
+        Some(total) => tracing::info!(target: TARGET, label, done, total),
+        None => tracing::info!(target: TARGET, label, done),
+    }
+}
+
+/// A one line remark about the run, like how many paths a search found
+pub fn note(message: impl Display) {
+    tracing::info!(target: TARGET, "{message}");
+}
+
+/// A drawing of the solution's state, only rendered when debug events are enabled
+pub fn picture(label: &'static str, picture: impl Display) {
+    tracing::debug!(target: TARGET, label, "\n{picture}");
+}
@@ -0,0 +1,132 @@
+use std::{fmt::Debug, panic::{catch_unwind, AssertUnwindSafe}};
+use itertools::Itertools;
+
+#[allow(dead_code, clippy::duplicate_mod)]
+#[path = "rng.rs"]
+mod rng;
+pub use rng::Rng;
+
+/// Cases per property, `PROPERTY_CASES` overrides it
+const CASES: usize = 200;
+/// Seed of the first case, `PROPERTY_SEED` overrides it to replay a run
+const SEED: u64 = 2024;
+
+/// A value that can propose simpler versions of itself to narrow down a failure
+pub trait Shrink: Clone + Debug {
+    /// Strictly smaller values, most aggressive first
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! impl_shrink {
+    ($($t:ty),*) => {
+        $(impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                [0, *self / 2, self.saturating_sub(1)].into_iter().filter(|v| v < self).dedup().collect()
+            }
+        })*
+    };
+}
+
+impl_shrink!(u8, u32, u64, usize);
+
+impl<T: Shrink> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let mut smaller = vec![];
+        if self.len() > 1 {
+            smaller.push(self[..self.len() / 2].to_vec());
+            smaller.push(self[self.len() / 2..].to_vec());
+        }
+        (0..self.len()).for_each(|i| {
+            let mut without = self.clone();
+            without.remove(i);
+            smaller.push(without);
+        });
+        self.iter().enumerate().for_each(|(i, value)| value.shrink().into_iter().for_each(|value| {
+            let mut simpler = self.clone();
+            simpler[i] = value;
+            smaller.push(simpler);
+        }));
+
+        smaller
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        self.0.shrink().into_iter().map(|a| (a, self.1.clone()))
+            .chain(self.1.shrink().into_iter().map(|b| (self.0.clone(), b)))
+            .collect()
+    }
+}
+
+impl<A: Shrink, B: Shrink, C: Shrink> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<Self> {
+        ((self.0.clone(), self.1.clone()), self.2.clone()).shrink().into_iter()
+            .map(|((a, b), c)| (a, b, c))
+            .collect()
+    }
+}
+
+fn setting<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+/// A panic counts as the property not holding
+fn holds<T>(property: &impl Fn(&T) -> bool, input: &T) -> bool {
+    catch_unwind(AssertUnwindSafe(|| property(input))).unwrap_or(false)
+}
+
+/// Greedily take the first simpler input that still fails until none does
+pub fn shrink<T: Shrink>(property: &impl Fn(&T) -> bool, mut input: T) -> (T, usize) {
+    let mut steps = 0;
+    while let Some(smaller) = input.shrink().into_iter().find(|candidate| !holds(property, candidate)) {
+        input = smaller;
+        steps += 1;
+    }
+
+    (input, steps)
+}
+
+/// Check the property on random inputs and panic with the smallest failing input found.
+/// Properties return true for inputs outside of what they cover, so shrinking can't wander off into those
+pub fn check<T: Shrink>(generate: impl Fn(&mut Rng) -> T, property: impl Fn(&T) -> bool) {
+    let seed = setting("PROPERTY_SEED", SEED);
+    let mut rng = Rng::new(seed);
+    for case in 0..setting("PROPERTY_CASES", CASES) {
+        let input = generate(&mut rng);
+        if !holds(&property, &input) {
+            let (minimal, steps) = shrink(&property, input.clone());
+            panic!("Property failed in case {case} of seed {seed}\ninput: {input:?}\nshrunk in {steps} steps to: {minimal:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{check, shrink, Shrink};
+
+    #[test]
+    fn test_shrink() {
+        assert_eq!(10u32.shrink(), vec![0, 5, 9]);
+        assert_eq!(1u32.shrink(), vec![0]);
+        assert!(0u32.shrink().is_empty());
+        assert_eq!(vec![1u8, 2].shrink(), vec![vec![1], vec![2], vec![2], vec![1], vec![0, 2], vec![1, 0], vec![1, 1]]);
+
+        let sum_below_ten = |values: &Vec<u32>| values.iter().sum::<u32>() < 10;
+        assert_eq!(shrink(&sum_below_ten, vec![3, 8, 1, 40, 2]), (vec![2, 8], 2));
+        let ordered = |(a, b): &(u64, u64)| a <= b;
+        assert_eq!(shrink(&ordered, (90, 31)).0, (1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "shrunk in")]
+    fn test_check_reports() {
+        check(|rng| rng.below(100) as u32, |n| *n < 50);
+    }
+
+    #[test]
+    fn test_check_passes() {
+        check(|rng| (rng.below(100), rng.below(100)), |(a, b)| a + b == b + a);
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    io::IsTerminal,
+    path::PathBuf,
+    sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex},
+    time::{Duration, Instant},
+};
+use serde_json::{json, Map, Value};
+use tracing::{field::{Field, Visit}, span, Event, Level, Subscriber};
+use tracing_subscriber::{filter::{filter_fn, Targets}, fmt::layer, layer::{Context, SubscriberExt}, registry::LookupSpan, util::SubscriberInitExt, Layer};
+
+/// Variable naming the file the Chrome trace-event JSON is written to
+const TRACE_VAR: &str = "AOC_TRACE";
+/// Variable choosing how progress events are shown, `bar`, `log` or `off`
+const PROGRESS_VAR: &str = "AOC_PROGRESS";
+/// Target the solutions' `progress` module reports on
+const PROGRESS_TARGET: &str = "progress";
+const BAR_WIDTH: u64 = 30;
+
+/// Busy time of all spans with the same path from the root
+#[derive(Debug)]
+struct Node {
+    path: Vec<&'static str>,
+    calls: u32,
+    busy: Duration,
+}
+
+/// Span durations in the order the spans were first opened
+#[derive(Debug, Default)]
+pub struct SpanTree {
+    nodes: Vec<Node>,
+    index: HashMap<Vec<&'static str>, usize>,
+}
+
+impl SpanTree {
+    fn node(&mut self, path: Vec<&'static str>) -> usize {
+        *self.index.entry(path.clone()).or_insert_with(|| {
+            self.nodes.push(Node { path, calls: 0, busy: Duration::ZERO });
+            self.nodes.len() - 1
+        })
+    }
+}
+
+impl Display for SpanTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            let name = format!("{:indent$}{}", "", node.path.last().unwrap_or(&""), indent = 2 * (node.path.len() - 1));
+            write!(f, "{name:<30} {:>12.2?}", node.busy)?;
+            if node.calls > 1 {
+                write!(f, " ({} calls)", node.calls)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Time a span was entered for, kept in the span's extensions
+struct Busy {
+    node: usize,
+    busy: Duration,
+    entered: Option<Instant>,
+}
+
+#[derive(Clone, Default)]
+struct Timings {
+    tree: Arc<Mutex<SpanTree>>,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Timings {
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let path = span.scope().from_root().map(|span| span.name()).collect();
+        let node = self.tree.lock().unwrap().node(path);
+        span.extensions_mut().insert(Busy { node, busy: Duration::ZERO, entered: None });
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(busy) = extensions.get_mut::<Busy>() {
+            busy.entered = Some(Instant::now());
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(busy) = extensions.get_mut::<Busy>() {
+            busy.busy += busy.entered.take().map_or(Duration::ZERO, |entered| entered.elapsed());
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let busy = span.extensions_mut().remove::<Busy>();
+        if let Some(busy) = busy {
+            let node = &mut self.tree.lock().unwrap().nodes[busy.node];
+            node.calls += 1;
+            node.busy += busy.busy;
+        }
+    }
+}
+
+/// Small ids for the `tid` field, `ThreadId` has no stable number
+fn thread_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static ID: u64 = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    ID.with(|id| *id)
+}
+
+#[derive(Default)]
+struct Fields(Map<String, Value>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), Value::String(format!("{value:?}")));
+    }
+}
+
+/// Trace-event JSON as read by `chrome://tracing` and Perfetto
+#[derive(Clone)]
+struct ChromeTrace {
+    start: Instant,
+    events: Arc<Mutex<Vec<Value>>>,
+}
+
+impl ChromeTrace {
+    fn push(&self, name: &str, target: &str, phase: &str, args: Map<String, Value>) {
+        self.events.lock().unwrap().push(json!({
+            "name": name,
+            "cat": target,
+            "ph": phase,
+            "ts": self.start.elapsed().as_secs_f64() * 1e6,
+            "pid": std::process::id(),
+            "tid": thread_id(),
+            "args": args,
+        }));
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "traceEvents": *self.events.lock().unwrap() })
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for ChromeTrace {
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            self.push(span.name(), span.metadata().target(), "B", Map::new());
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            self.push(span.name(), span.metadata().target(), "E", Map::new());
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        fields.0.insert("s".to_string(), Value::String("t".to_string()));
+        self.push(event.metadata().name(), event.metadata().target(), "i", fields.0);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProgressMode {
+    /// One line on stderr redrawn in place, notes printed above it
+    Bar,
+    /// Every event as a log line
+    Log,
+    Off,
+}
+
+impl ProgressMode {
+    /// From `AOC_PROGRESS`, a bar on terminals and log lines everywhere else by default
+    fn from_env() -> Self {
+        let default = if std::io::stderr().is_terminal() { Self::Bar } else { Self::Log };
+        match std::env::var(PROGRESS_VAR).as_deref() {
+            Ok("bar") => Self::Bar,
+            Ok("log") => Self::Log,
+            Ok("off") => Self::Off,
+            Ok(mode) => {
+                eprintln!("Ignoring {PROGRESS_VAR}={mode:?}, expected bar, log or off");
+                default
+            },
+            Err(_) => default,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ProgressFields {
+    label: String,
+    done: Option<u64>,
+    total: Option<u64>,
+    message: String,
+}
+
+impl Visit for ProgressFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "label" => self.label = value.to_string(),
+            _ => self.record_debug(field, &value),
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "done" => self.done = Some(value),
+            "total" => self.total = Some(value),
+            _ => self.record_debug(field, &value),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        }
+    }
+}
+
+/// `label [=====>    ] done/total`, or just the count when the total isn't known
+fn render_bar(label: &str, done: u64, total: Option<u64>) -> String {
+    match total {
+        Some(total) if total > 0 => {
+            let filled = (done.min(total) * BAR_WIDTH / total) as usize;
+            let bar = format!("{}{}", "=".repeat(filled), if filled < BAR_WIDTH as usize { ">" } else { "" });
+            format!("{label} [{bar:<width$}] {done}/{total}", width = BAR_WIDTH as usize)
+        },
+        _ => format!("{label} {done}"),
+    }
+}
+
+/// Draws progress events as a bar on stderr that is cleared when the part's span closes
+#[derive(Default)]
+struct ProgressBar {
+    drawn: AtomicBool,
+}
+
+impl ProgressBar {
+    fn clear(&self) {
+        if self.drawn.swap(false, Ordering::Relaxed) {
+            eprint!("\r\x1b[K");
+        }
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for ProgressBar {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != PROGRESS_TARGET {
+            return;
+        }
+        let mut fields = ProgressFields::default();
+        event.record(&mut fields);
+
+        match fields.done {
+            Some(done) => {
+                eprint!("\r\x1b[K{}", render_bar(&fields.label, done, fields.total));
+                self.drawn.store(true, Ordering::Relaxed);
+            },
+            None => {
+                self.clear();
+                eprintln!("{}", fields.message);
+            },
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if ctx.span(&id).is_some_and(|span| span.parent().is_none()) {
+            self.clear();
+        }
+    }
+}
+
+/// Prints the span tree and writes the trace when dropped at the end of `main`
+pub struct Telemetry {
+    tree: Arc<Mutex<SpanTree>>,
+    trace: Option<(PathBuf, ChromeTrace)>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        let tree = self.tree.lock().unwrap();
+        if !tree.nodes.is_empty() {
+            eprint!("\n{tree}");
+        }
+
+        if let Some((path, trace)) = &self.trace {
+            match std::fs::write(path, trace.to_json().to_string()) {
+                Ok(()) => eprintln!("Trace written to {}", path.display()),
+                Err(error) => eprintln!("Failed to write trace to {}: {error}", path.display()),
+            }
+        }
+    }
+}
+
+/// Logs to stderr filtered by `RUST_LOG` (`info` by default) and times every span, set `AOC_TRACE` to also record a Chrome trace
+/// and `AOC_PROGRESS` to pick how the solutions' progress is shown
+pub fn init() -> Telemetry {
+    let filter = match std::env::var("RUST_LOG") {
+        Ok(directives) => directives.parse::<Targets>().unwrap_or_else(|error| {
+            eprintln!("Ignoring RUST_LOG={directives:?}: {error}");
+            Targets::new().with_default(Level::INFO)
+        }),
+        Err(_) => Targets::new().with_default(Level::INFO),
+    };
+    let timings = Timings::default();
+    let trace = std::env::var_os(TRACE_VAR).map(|path| (PathBuf::from(path), ChromeTrace {
+        start: Instant::now(),
+        events: Arc::default(),
+    }));
+
+    let progress = ProgressMode::from_env();
+
+    tracing_subscriber::registry()
+        .with(layer().with_writer(std::io::stderr).with_filter(filter_fn(move |metadata| {
+            progress == ProgressMode::Log || metadata.target() != PROGRESS_TARGET
+        })))
+        .with((progress == ProgressMode::Bar).then(ProgressBar::default))
+        .with(timings.clone())
+        .with(trace.as_ref().map(|(_, trace)| trace.clone()))
+        .with(filter)
+        .init();
+
+    Telemetry { tree: timings.tree, trace }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use tracing::info_span;
+    use tracing_subscriber::layer::SubscriberExt;
+    use super::{render_bar, ChromeTrace, Timings};
+
+    #[test]
+    fn test_span_tree_and_trace() {
+        let timings = Timings::default();
+        let trace = ChromeTrace { start: Instant::now(), events: Arc::new(Mutex::new(vec![])) };
+        let subscriber = tracing_subscriber::registry().with(timings.clone()).with(trace.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("parse").in_scope(|| {});
+            info_span!("part1").in_scope(|| {
+                (0..3).for_each(|_| info_span!("step").in_scope(|| tracing::info!(answer = 42)));
+            });
+        });
+
+        let tree = timings.tree.lock().unwrap().to_string();
+        let names = tree.lines().map(|line| line.split_whitespace().next().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names, ["parse", "part1", "step"]);
+        assert!(tree.lines().nth(2).unwrap().starts_with("  step "));
+        assert!(tree.lines().nth(2).unwrap().ends_with("(3 calls)"));
+
+        let json = trace.to_json();
+        let events = json["traceEvents"].as_array().unwrap();
+        assert_eq!(events.iter().map(|e| e["ph"].as_str().unwrap()).collect::<String>(), "BEBBiEBiEBiEE");
+        assert_eq!(events[4]["args"]["answer"], "42");
+    }
+
+    #[test]
+    fn test_render_bar() {
+        assert_eq!(render_bar("robots", 5, Some(10)), "robots [===============>              ] 5/10");
+        assert_eq!(render_bar("robots", 12, Some(10)), format!("robots [{}] 12/10", "=".repeat(30)));
+        assert_eq!(render_bar("candidates", 3_000_000, None), "candidates 3000000");
+    }
+}
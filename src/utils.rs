@@ -1,5 +1,9 @@
 use miette::Result;
 pub trait Solution<T> {
-    fn part1(input: &str) -> Result<T>;
-    fn part2(input: &str) -> Result<T>;
+    /// Input of both parts, parsed once by the runner
+    type Parsed;
+
+    fn parse(input: &str) -> Result<Self::Parsed>;
+    fn part1(parsed: &Self::Parsed) -> Result<T>;
+    fn part2(parsed: &Self::Parsed) -> Result<T>;
 }